@@ -0,0 +1,114 @@
+#[macro_use]
+extern crate criterion;
+extern crate quoted_string;
+
+use criterion::{Criterion, black_box};
+
+use quoted_string::test_utils::{TestSpec, TestUnquotedValidator};
+use quoted_string::{parse, validate, to_content, quote, quote_if_needed, ContentChars};
+
+// parameter values as they appear in Content-Type/Content-Disposition headers
+const SHORT: &str = "\"UTF-8\"";
+const FILENAME: &str = "\"Quarterly Report (final).pdf\"";
+const WITH_QUOTED_PAIRS: &str = r#""the \"final\" version \\ v2""#;
+const DISPLAY_NAME: &str = "\"Doe, John (Accounting)\"";
+
+fn long_input() -> String {
+    let mut out = String::from("\"");
+    for _ in 0..64 {
+        out.push_str("multipart boundary --=_Part_0_1234567890 ");
+    }
+    out.push('"');
+    out
+}
+
+fn pathological_input() -> String {
+    // every second char is a quoted-pair
+    let mut out = String::from("\"");
+    for _ in 0..1024 {
+        out.push_str("\\\"a");
+    }
+    out.push('"');
+    out
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let long = long_input();
+    let pathological = pathological_input();
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("short", |b| b.iter(|| parse::<TestSpec>(black_box(SHORT))));
+    group.bench_function("filename", |b| b.iter(|| parse::<TestSpec>(black_box(FILENAME))));
+    group.bench_function("long", |b| b.iter(|| parse::<TestSpec>(black_box(&long))));
+    group.bench_function("pathological", |b| {
+        b.iter(|| parse::<TestSpec>(black_box(&pathological)))
+    });
+    group.finish();
+}
+
+fn bench_validate(c: &mut Criterion) {
+    let long = long_input();
+    let mut group = c.benchmark_group("validate");
+    group.bench_function("display_name", |b| b.iter(|| validate::<TestSpec>(black_box(DISPLAY_NAME))));
+    group.bench_function("long", |b| b.iter(|| validate::<TestSpec>(black_box(&long))));
+    group.finish();
+}
+
+fn bench_to_content(c: &mut Criterion) {
+    let pathological = pathological_input();
+    let mut group = c.benchmark_group("to_content");
+    group.bench_function("borrowed", |b| b.iter(|| to_content::<TestSpec>(black_box(FILENAME))));
+    group.bench_function("owned", |b| {
+        b.iter(|| to_content::<TestSpec>(black_box(WITH_QUOTED_PAIRS)))
+    });
+    group.bench_function("pathological", |b| {
+        b.iter(|| to_content::<TestSpec>(black_box(&pathological)))
+    });
+    group.finish();
+}
+
+fn bench_quote(c: &mut Criterion) {
+    let long = long_input();
+    let long_content = &long[1..long.len()-1];
+    let mut group = c.benchmark_group("quote");
+    group.bench_function("quote_filename", |b| {
+        b.iter(|| quote::<TestSpec>(black_box("Quarterly Report (final).pdf")))
+    });
+    group.bench_function("quote_with_escapes", |b| {
+        b.iter(|| quote::<TestSpec>(black_box(r#"the "final" version \ v2"#)))
+    });
+    group.bench_function("quote_long_ascii", |b| {
+        b.iter(|| quote::<TestSpec>(black_box(long_content)))
+    });
+    group.bench_function("quote_if_needed_unneeded", |b| {
+        b.iter(|| {
+            let mut validator = TestUnquotedValidator::new();
+            quote_if_needed::<TestSpec, _>(black_box("abcdef"), &mut validator)
+        })
+    });
+    group.bench_function("quote_if_needed_needed", |b| {
+        b.iter(|| {
+            let mut validator = TestUnquotedValidator::new();
+            quote_if_needed::<TestSpec, _>(black_box("Doe, John (Accounting)"), &mut validator)
+        })
+    });
+    group.finish();
+}
+
+fn bench_content_chars(c: &mut Criterion) {
+    let long = long_input();
+    let mut group = c.benchmark_group("content_chars");
+    group.bench_function("with_quoted_pairs", |b| {
+        b.iter(|| ContentChars::<TestSpec>::from_str(black_box(WITH_QUOTED_PAIRS)).count())
+    });
+    group.bench_function("long", |b| {
+        b.iter(|| ContentChars::<TestSpec>::from_str(black_box(&long)).count())
+    });
+    group.bench_function("eq_str", |b| {
+        b.iter(|| ContentChars::<TestSpec>::from_str(black_box(WITH_QUOTED_PAIRS))
+            == black_box(r#"the "final" version \ v2"#))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_validate, bench_to_content, bench_quote, bench_content_chars);
+criterion_main!(benches);
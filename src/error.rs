@@ -2,7 +2,12 @@
 use std::error::{Error as StdError};
 use std::fmt::{self, Display};
 
+/// errors which can occur when parsing, unquoting or quoting
+///
+/// New variants might be added in the future, so matching on
+/// it requires a wildcard arm.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+#[non_exhaustive]
 pub enum CoreError {
     AdvancedFailedAutomaton,
     QuotedStringAlreadyEnded,
@@ -10,7 +15,15 @@ pub enum CoreError {
     DoesNotStartWithDQuotes,
     DoesNotEndWithDQuotes,
     InvalidChar,
-    ZeroSizedValue
+    ZeroSizedValue,
+    WriteFailed,
+    MissingColon,
+    UnclosedComment,
+    PostValidationFailed,
+    MissingEqualsSign,
+    DoesNotStartWithParenthesis,
+    LimitExceeded,
+    UnexpectedTrailingInput
 }
 
 impl CoreError {
@@ -25,6 +38,14 @@ impl CoreError {
             DoesNotEndWithDQuotes => 4,
             InvalidChar => 5,
             ZeroSizedValue => 6,
+            WriteFailed => 7,
+            MissingColon => 8,
+            UnclosedComment => 9,
+            PostValidationFailed => 10,
+            MissingEqualsSign => 11,
+            DoesNotStartWithParenthesis => 12,
+            LimitExceeded => 13,
+            UnexpectedTrailingInput => 14,
         }
     }
 
@@ -38,11 +59,79 @@ impl CoreError {
             4 => DoesNotEndWithDQuotes,
             5 => InvalidChar,
             6 => ZeroSizedValue,
+            7 => WriteFailed,
+            8 => MissingColon,
+            9 => UnclosedComment,
+            10 => PostValidationFailed,
+            11 => MissingEqualsSign,
+            12 => DoesNotStartWithParenthesis,
+            13 => LimitExceeded,
+            14 => UnexpectedTrailingInput,
             _ => return None
         })
     }
+
+    /// returns a message describing the error suitable for end users
+    ///
+    /// In difference to `description`, which describes the error in terms
+    /// of the parser internals, this message is meant to be shown to the
+    /// user of a application. The messages are stable, so they can be used
+    /// as keys for localization.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quoted_string::error::CoreError;
+    ///
+    /// assert_eq!(
+    ///     CoreError::DoesNotStartWithDQuotes.user_message(),
+    ///     "A quoted string must begin with a double-quote character"
+    /// );
+    /// ```
+    pub fn user_message(&self) -> &'static str {
+        use self::CoreError::*;
+        match *self {
+            AdvancedFailedAutomaton =>
+                "The quoted string could not be processed after an earlier error",
+            QuotedStringAlreadyEnded =>
+                "There is unexpected text after the closing double-quote character",
+            UnquoteableCharQuoted =>
+                "A character was escaped with a backslash which can not be escaped",
+            DoesNotStartWithDQuotes =>
+                "A quoted string must begin with a double-quote character",
+            DoesNotEndWithDQuotes =>
+                "A quoted string must end with a double-quote character",
+            InvalidChar =>
+                "The text contains a character which is not allowed",
+            ZeroSizedValue =>
+                "The value must not be empty",
+            WriteFailed =>
+                "The text could not be written",
+            MissingColon =>
+                "A header must contain a colon separating the name from the value",
+            UnclosedComment =>
+                "A comment must be closed with a closing parenthesis",
+            PostValidationFailed =>
+                "The quoted string is not allowed in this context",
+            MissingEqualsSign =>
+                "A parameter must contain an equals sign separating the name from the value",
+            DoesNotStartWithParenthesis =>
+                "A comment must begin with an opening parenthesis",
+            LimitExceeded =>
+                "The quoted string is too long",
+            UnexpectedTrailingInput =>
+                "There is unexpected text after the value"
+        }
+    }
 }
+impl From<fmt::Error> for CoreError {
+    fn from(_err: fmt::Error) -> Self {
+        CoreError::WriteFailed
+    }
+}
+
 impl Display for CoreError {
+    #[allow(deprecated)]
     fn fmt(&self, fter: &mut fmt::Formatter) -> fmt::Result {
         fter.write_str(self.description())
     }
@@ -65,7 +154,81 @@ impl StdError for CoreError {
             InvalidChar =>
                 "char can not be represented in a quoted string (without encoding)",
             ZeroSizedValue =>
-                "value had a size of zero chars/bytes but has to have at last one"
+                "value had a size of zero chars/bytes but has to have at last one",
+            WriteFailed =>
+                "writing to the output failed",
+            MissingColon =>
+                "header line did not contain a ':' separating the name from the value",
+            UnclosedComment =>
+                "a comment was opened with '(' but not closed with ')'",
+            PostValidationFailed =>
+                "the quoted string was rejected by the custom validation",
+            MissingEqualsSign =>
+                "parameter did not contain a '=' separating the name from the value",
+            DoesNotStartWithParenthesis =>
+                "comment did not start with (",
+            LimitExceeded =>
+                "quoted string exceeded the configured length limit",
+            UnexpectedTrailingInput =>
+                "a value was followed by input which is neither white space nor a separator"
         }
     }
 }
+
+/// error returned by `to_content_ascii_only`
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum AsciiOnlyError {
+    /// the input was not a valid quoted string
+    Core(CoreError),
+    /// the decoded content contained a non us-ascii char
+    NonAsciiAt {
+        /// the byte offset of the char in the decoded content
+        byte_offset: usize,
+        /// the non us-ascii char
+        char_value: char
+    }
+}
+
+impl From<CoreError> for AsciiOnlyError {
+    fn from(err: CoreError) -> Self {
+        AsciiOnlyError::Core(err)
+    }
+}
+
+impl Display for AsciiOnlyError {
+    fn fmt(&self, fter: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AsciiOnlyError::Core(ref err) => Display::fmt(err, fter),
+            AsciiOnlyError::NonAsciiAt { byte_offset, char_value } =>
+                write!(fter, "content contained non us-ascii char {:?} at byte offset {}",
+                       char_value, byte_offset)
+        }
+    }
+}
+
+impl StdError for AsciiOnlyError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match *self {
+            AsciiOnlyError::Core(ref err) => Some(err),
+            AsciiOnlyError::NonAsciiAt { .. } => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::error::{Error as StdError};
+    use super::{CoreError, AsciiOnlyError};
+
+    fn assert_boxable<E: StdError + Send + Sync + 'static>(err: E) -> Box<dyn StdError + Send + Sync> {
+        Box::from(err)
+    }
+
+    #[test]
+    fn errors_convert_into_boxed_error() {
+        let boxed = assert_boxable(CoreError::InvalidChar);
+        assert_eq!(boxed.downcast_ref::<CoreError>(), Some(&CoreError::InvalidChar));
+        let boxed = assert_boxable(AsciiOnlyError::from(CoreError::InvalidChar));
+        assert!(boxed.source().is_some());
+    }
+}
@@ -1,13 +1,17 @@
 use std::str::Chars;
-use std::iter::Iterator;
+use std::iter::{Iterator, Sum, FromIterator};
 use std::cmp::{ PartialEq };
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, Read, BufRead};
 
 use error::CoreError;
-use spec::{GeneralQSSpec, ScanAutomaton, PartialCodePoint};
+use spec::{GeneralQSSpec, ScanAutomaton, PartialCodePoint, State};
+use parse::parse;
 // this import will become unused in future rust versions
 // but won't be removed for now for supporting current
 // rust versions
-#[allow(warnings)]
+#[allow(unused_imports, deprecated)]
 use std::ascii::AsciiExt;
 
 /// Analogous to PartialEq, but with _ascii_ case insensitive equality
@@ -55,10 +59,15 @@ pub trait AsciiCaseInsensitiveEq<Rhs: ?Sized> {
 /// assert_eq!(cc.collect::<Result<Vec<_>,_>>().unwrap().as_slice(), &[ 'a', 'b', '"', ' ', 'c' ] );
 ///
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ContentChars<'a, Impl: GeneralQSSpec> {
     inner: Chars<'a>,
-    automaton: ScanAutomaton<Impl::Parsing>
+    automaton: ScanAutomaton<Impl::Parsing>,
+    // the input and automaton it was created with, used by `rewind`
+    start: &'a str,
+    start_automaton: ScanAutomaton<Impl::Parsing>,
+    // error returned by `GeneralQSSpec::pre_validate` in `from_str`
+    pre_validation_error: Option<CoreError>
 }
 
 impl<'s, Impl> ContentChars<'s, Impl>
@@ -69,11 +78,15 @@ impl<'s, Impl> ContentChars<'s, Impl>
     ///
     /// the quoted string is _assumed_ to be valid and not explicitely checked for validity
     /// but because of the way unquoting works a number of error can be detected
+    ///
+    /// `GeneralQSSpec::pre_validate` is called with `quoted`, if it fails
+    /// the iterator only yields the error it returned.
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(quoted: &'s str) -> Self {
-        ContentChars {
-            inner: quoted.chars(),
-            automaton: ScanAutomaton::<Impl::Parsing>::new()
-        }
+        let mut chars =
+            ContentChars::from_parts_unchecked(quoted, ScanAutomaton::<Impl::Parsing>::new());
+        chars.pre_validation_error = Impl::pre_validate(quoted).err();
+        chars
     }
 
     /// creates a ContentChars iterator from a str and a QuotedValidator
@@ -91,10 +104,833 @@ impl<'s, Impl> ContentChars<'s, Impl>
     ) -> Self
     {
         let inner = partial_quoted_content.chars();
-        ContentChars{ inner, automaton }
+        ContentChars {
+            inner,
+            start_automaton: automaton.clone(),
+            automaton,
+            start: partial_quoted_content,
+            pre_validation_error: None
+        }
+    }
+
+    /// returns an iterator which fails on any decoded non us-ascii char
+    ///
+    /// This is useful for contexts where the content has to be us-ascii
+    /// (e.g. RFC 5322 without the RFC 6532 utf-8 extension), if a decoded
+    /// char is non us-ascii `CoreError::InvalidChar` is returned for it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    /// use quoted_string::error::CoreError;
+    ///
+    /// let res = ContentChars::<TestSpec>::from_str("\"abc\"")
+    ///     .ascii_only()
+    ///     .collect::<Result<String, _>>();
+    /// assert_eq!(res, Ok("abc".to_owned()));
+    /// ```
+    pub fn ascii_only(self) -> ContentCharsAsciiOnly<'s, Impl> {
+        ContentCharsAsciiOnly { inner: self }
+    }
+
+    /// collects the content into a `String`
+    ///
+    /// returns the first error encountered if the quoted string is invalid
+    pub fn into_string(self) -> Result<String, CoreError> {
+        self.collect()
+    }
+
+    /// collects the remaining content into a `String` without consuming the iterator
+    ///
+    /// This is `into_string` called on a clone of the iterator, `self`
+    /// is left untouched and can still be used afterwards.
+    pub fn clone_and_collect(&self) -> Result<String, CoreError> {
+        self.clone().into_string()
+    }
+
+    /// returns true if there are no more chars in the content
+    ///
+    /// Non-semantic parts (e.g. folding white space) before the next
+    /// char are skipped, so this is true iff `next` would return `None`.
+    /// If `next` would return an error this is false. `self` is not
+    /// modified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// assert!(ContentChars::<TestSpec>::from_str("\"\"").is_empty());
+    /// assert!(ContentChars::<TestSpec>::from_str("\"\n\n\"").is_empty());
+    /// assert!(!ContentChars::<TestSpec>::from_str("\"a\"").is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.clone().next().is_none()
+    }
+
+    /// collects the utf-8 encoded content into a `Vec<u8>`
+    ///
+    /// returns the first error encountered if the quoted string is invalid
+    pub fn collect_bytes(self) -> Result<Vec<u8>, CoreError> {
+        let mut out = Vec::with_capacity(self.inner.as_str().len());
+        let mut buf = [0u8; 4];
+        for res in self {
+            let ch = res?;
+            out.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+        }
+        Ok(out)
+    }
+
+    /// returns the utf-8 byte length of the content
+    ///
+    /// returns the first error encountered if the quoted string is invalid
+    pub fn byte_len_decoded(self) -> Result<usize, CoreError> {
+        let mut len = 0;
+        for res in self {
+            len += res?.len_utf8();
+        }
+        Ok(len)
+    }
+
+    /// collects the content into a `String` replacing errors with `replacement`
+    ///
+    /// As the quoted string can not be decoded after an error was
+    /// encountered, the first error is replaced with `replacement`
+    /// and the remaining input is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let content = ContentChars::<TestSpec>::from_str("\"abc")
+    ///     .into_string_lossy('\u{FFFD}');
+    /// assert_eq!(content, "abc\u{FFFD}");
+    /// ```
+    pub fn into_string_lossy(self, replacement: char) -> String {
+        let mut out = String::new();
+        for res in self {
+            match res {
+                Ok(ch) => out.push(ch),
+                Err(_) => {
+                    out.push(replacement);
+                    break;
+                }
+            }
+        }
+        out
+    }
+
+    /// returns an iterator pairing each decoded char with it's representation in the input
+    ///
+    /// The representation is either the char itself or the quoted-pair
+    /// (e.g. `\"`) representing it. Non-semantic parts of the input are not
+    /// included in any representation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let pairs = ContentChars::<TestSpec>::from_str(r#""a\"""#)
+    ///     .zip_with_original()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(pairs, &[('a', "a"), ('"', r#"\""#)]);
+    /// ```
+    pub fn zip_with_original(self) -> ContentCharsWithOriginal<'s, Impl> {
+        ContentCharsWithOriginal { inner: self }
+    }
+
+    /// returns an iterator pairing each decoded char with it's index in the decoded content
+    ///
+    /// This is analogous to `str::char_indices`, but the index is the
+    /// position in the sequence of decoded chars and not a byte offset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let pairs = ContentChars::<TestSpec>::from_str(r#""a\"b""#)
+    ///     .zip_positions()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(pairs, &[(0, 'a'), (1, '"'), (2, 'b')]);
+    /// ```
+    pub fn zip_positions(self) -> ContentCharsWithPositions<'s, Impl> {
+        ContentCharsWithPositions { inner: self, next_pos: 0 }
+    }
+
+    /// returns a reader over the utf-8 encoded content
+    ///
+    /// The reader implements `BufRead`, so e.g. `lines` can be used to
+    /// split the content at decoded `'\n'` chars (if the spec allows them
+    /// in the content). Errors are returned as `io::Error` of kind
+    /// `InvalidData` wrapping the `CoreError`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::BufRead;
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let lines = ContentChars::<TestSpec>::from_str(r#""a\"b""#)
+    ///     .into_reader()
+    ///     .lines()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(lines, &["a\"b"]);
+    /// ```
+    pub fn into_reader(self) -> ContentCharsReader<'s, Impl> {
+        ContentCharsReader { inner: self, buf: [0; 4], pos: 0, len: 0, pending_error: None }
+    }
+
+    /// returns an iterator over all windows of `size` consecutive decoded chars
+    ///
+    /// Like `slice::windows` the windows overlap and no window is returned if the
+    /// content is shorter than `size`. If an error is encountered it is returned
+    /// and the iteration ends.
+    ///
+    /// # Panics
+    ///
+    /// if `size` is 0
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let windows = ContentChars::<TestSpec>::from_str(r#""a\"b""#)
+    ///     .windows(2)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(windows, vec![vec!['a', '"'], vec!['"', 'b']]);
+    /// ```
+    pub fn windows(self, size: usize) -> ContentCharsWindows<'s, Impl> {
+        assert!(size != 0, "window size must be non-zero");
+        ContentCharsWindows { inner: self, size, window: Vec::with_capacity(size), done: false }
+    }
+
+    /// returns an iterator applying `func` to each decoded char
+    ///
+    /// Errors are passed through unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let upper = ContentChars::<TestSpec>::from_str(r#""a\"b""#)
+    ///     .map_chars(|ch| ch.to_ascii_uppercase())
+    ///     .collect::<Result<String, _>>();
+    /// assert_eq!(upper, Ok("A\"B".to_owned()));
+    /// ```
+    pub fn map_chars<F>(self, func: F) -> MappedContentChars<'s, Impl, F>
+        where F: FnMut(char) -> char
+    {
+        MappedContentChars { inner: self, func }
+    }
+
+    /// returns an iterator applying `func` to each error
+    ///
+    /// Decoded chars are passed through unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct MyError;
+    ///
+    /// let res = ContentChars::<TestSpec>::from_str("\"ab")
+    ///     .map_err(|_| MyError)
+    ///     .collect::<Result<String, _>>();
+    /// assert_eq!(res, Err(MyError));
+    /// ```
+    pub fn map_err<F, E>(self, func: F) -> MappedErrContentChars<'s, Impl, F>
+        where F: FnMut(CoreError) -> E
+    {
+        MappedErrContentChars { inner: self, func }
+    }
+
+    /// returns an iterator yielding the content of `self` followed by the content of `other`
+    ///
+    /// Errors of either of the two quoted strings are passed through unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let first = ContentChars::<TestSpec>::from_str(r#""a\"b""#);
+    /// let second = ContentChars::<TestSpec>::from_str(r#"" c""#);
+    /// let joined = first.chain_decoded(second).collect::<Result<String, _>>();
+    /// assert_eq!(joined, Ok("a\"b c".to_owned()));
+    /// ```
+    pub fn chain_decoded<'o>(self, other: ContentChars<'o, Impl>) -> ChainedContentChars<'s, 'o, Impl> {
+        ChainedContentChars { first: self, second: other, first_done: false }
+    }
+
+    /// advances the iterator by `n` items returning the remaining iterator
+    ///
+    /// If a error is encountered while advancing it is returned. If there
+    /// are less than `n` items the returned iterator is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let rest = ContentChars::<TestSpec>::from_str(r#""a\"bc""#).drain(2).unwrap();
+    /// assert_eq!(rest, "bc");
+    /// ```
+    pub fn drain(mut self, n: usize) -> Result<Self, CoreError> {
+        for res in self.by_ref().take(n) {
+            res?;
+        }
+        Ok(self)
+    }
+
+    /// advances the iterator by `n` items returning the remaining iterator
+    ///
+    /// Like with `drain` errors encountered while advancing are returned.
+    /// If there are less than `n` items the returned iterator is empty.
+    pub fn drop_n(mut self, n: usize) -> Result<Self, CoreError> {
+        for res in self.by_ref().take(n) {
+            res?;
+        }
+        Ok(self)
+    }
+
+    /// collects (at most) the first `n` chars of the content into a `String`
+    ///
+    /// The rest of the content is not decoded, so errors in it are not
+    /// detected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let start = ContentChars::<TestSpec>::from_str(r#""a\"bc"#).take_n(2);
+    /// assert_eq!(start, Ok("a\"".to_owned()));
+    /// ```
+    pub fn take_n(self, n: usize) -> Result<String, CoreError> {
+        self.take(n).collect()
+    }
+
+    /// resets the iterator to the start of the input it was created from
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let mut cc = ContentChars::<TestSpec>::from_str("\"ab\"");
+    /// assert_eq!(cc.next(), Some(Ok('a')));
+    /// cc.rewind();
+    /// assert_eq!(cc, "ab");
+    /// ```
+    pub fn rewind(&mut self) {
+        self.inner = self.start.chars();
+        self.automaton = self.start_automaton.clone();
+    }
+
+    /// returns the number of quoted-pairs in the remaining input
+    ///
+    /// The remaining input is validated till the end of the quoted string,
+    /// the first error encountered is returned. `self` is not advanced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let cc = ContentChars::<TestSpec>::from_str(r#""a\"b\\""#);
+    /// assert_eq!(cc.count_escaped_pairs(), Ok(2));
+    /// ```
+    pub fn count_escaped_pairs(&self) -> Result<usize, CoreError> {
+        if let Some(err) = self.pre_validation_error {
+            return Err(err);
+        }
+        let mut automaton = self.automaton.clone();
+        let mut count = 0;
+        for ch in self.inner.clone() {
+            automaton.advance(PartialCodePoint::from_code_point(ch as u32))?;
+            if automaton.state() == State::QPStart {
+                count += 1;
+            }
+        }
+        automaton.end()?;
+        Ok(count)
+    }
+
+    /// returns the index of the first occurrence of `target` in the decoded content
+    ///
+    /// The index is the position in the sequence of decoded chars (not
+    /// a byte offset in the input). Returns `None` if `target` does not
+    /// occur and `Some(Err(..))` if an error is encountered before it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let cc = ContentChars::<TestSpec>::from_str(r#""a\"b""#);
+    /// assert_eq!(cc.find_char('b'), Some(Ok(2)));
+    /// assert_eq!(cc.find_char('c'), None);
+    /// ```
+    pub fn find_char(&self, target: char) -> Option<Result<usize, CoreError>> {
+        for (idx, res) in self.clone().enumerate() {
+            match res {
+                Ok(ch) if ch == target => return Some(Ok(idx)),
+                Ok(_) => {},
+                Err(err) => return Some(Err(err))
+            }
+        }
+        None
+    }
+
+    /// compares the content with `other` ignoring ascii case
+    ///
+    /// This is the same as `AsciiCaseInsensitiveEq::eq_ignore_ascii_case`
+    /// but can be used without importing the trait.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec instead
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::ContentChars;
+    ///
+    /// let cc = ContentChars::<TestSpec>::from_str(r#""a\"B""#);
+    /// assert!(cc.eq_str_ascii_ci("A\"b"));
+    /// assert!(!cc.eq_str_ascii_ci("a\"c"));
+    /// ```
+    pub fn eq_str_ascii_ci(&self, other: &str) -> bool {
+        AsciiCaseInsensitiveEq::<str>::eq_ignore_ascii_case(self, other)
+    }
+}
+
+/// A iterator over the chars of the content of a quoted string and their representation
+///
+/// It is created through `ContentChars::zip_with_original`.
+#[derive(Debug, Clone)]
+pub struct ContentCharsWithOriginal<'a, Impl: GeneralQSSpec> {
+    inner: ContentChars<'a, Impl>
+}
+
+impl<'a, Impl> Iterator for ContentCharsWithOriginal<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    type Item = Result<(char, &'a str), CoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ContentChars {
+            ref mut inner, ref mut automaton, pre_validation_error, ..
+        } = self.inner;
+        if let Some(err) = pre_validation_error {
+            return Some(Err(err));
+        }
+        let mut quoted_pair_start = None;
+        loop {
+            let before = inner.as_str();
+            if let Some(ch) = inner.next() {
+                match automaton.advance(PartialCodePoint::from_code_point(ch as u32)) {
+                    Err(e) => return Some(Err(e)),
+                    Ok(true) => {
+                        let raw_start = quoted_pair_start.unwrap_or(before);
+                        let raw_len = raw_start.len() - inner.as_str().len();
+                        return Some(Ok((ch, &raw_start[..raw_len])));
+                    },
+                    Ok(false) => {
+                        if automaton.state() == State::QPStart {
+                            quoted_pair_start = Some(before);
+                        }
+                    }
+                }
+            } else {
+                match automaton.end() {
+                    Err(e) => return Some(Err(e)),
+                    Ok(()) => return None
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A reader over the utf-8 encoded content of a quoted string
+///
+/// It is created through `ContentChars::into_reader`.
+#[derive(Debug, Clone)]
+pub struct ContentCharsReader<'a, Impl: GeneralQSSpec> {
+    inner: ContentChars<'a, Impl>,
+    // the utf-8 encoding of the last decoded char
+    buf: [u8; 4],
+    pos: usize,
+    len: usize,
+    // error hit by `read` after it already wrote some bytes, returned by the next call
+    pending_error: Option<CoreError>
+}
+
+impl<'a, Impl> ContentCharsReader<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    fn fill(&mut self) -> Result<&[u8], CoreError> {
+        if let Some(err) = self.pending_error.take() {
+            return Err(err);
+        }
+        if self.pos == self.len {
+            match self.inner.next() {
+                Some(Ok(ch)) => {
+                    self.len = ch.encode_utf8(&mut self.buf).len();
+                    self.pos = 0;
+                },
+                Some(Err(err)) => return Err(err),
+                None => {}
+            }
+        }
+        Ok(&self.buf[self.pos..self.len])
+    }
+}
+
+impl<'a, Impl> Read for ContentCharsReader<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let mut written = 0;
+        while written < out.len() {
+            let count = {
+                let available = match self.fill() {
+                    Ok(available) => available,
+                    Err(err) if written > 0 => {
+                        self.pending_error = Some(err);
+                        break;
+                    },
+                    Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err))
+                };
+                if available.is_empty() {
+                    break;
+                }
+                let count = available.len().min(out.len() - written);
+                out[written..written + count].copy_from_slice(&available[..count]);
+                count
+            };
+            self.consume(count);
+            written += count;
+        }
+        Ok(written)
+    }
+}
+
+impl<'a, Impl> BufRead for ContentCharsReader<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.fill().map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.len);
+    }
+}
+
+/// A iterator over the chars of the content of a quoted string and their index in it
+///
+/// It is created through `ContentChars::zip_positions`.
+#[derive(Debug, Clone)]
+pub struct ContentCharsWithPositions<'a, Impl: GeneralQSSpec> {
+    inner: ContentChars<'a, Impl>,
+    next_pos: usize
+}
+
+impl<'a, Impl> Iterator for ContentCharsWithPositions<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    type Item = Result<(usize, char), CoreError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.next_pos;
+        let res = self.inner.next()?;
+        Some(res.map(|ch| {
+            self.next_pos += 1;
+            (pos, ch)
+        }))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A iterator over the chars of the content of a quoted string which errors on non us-ascii chars
+///
+/// It is created through `ContentChars::ascii_only`.
+#[derive(Debug, Clone)]
+pub struct ContentCharsAsciiOnly<'a, Impl: GeneralQSSpec> {
+    inner: ContentChars<'a, Impl>
+}
+
+impl<'a, Impl> Iterator for ContentCharsAsciiOnly<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    type Item = Result<char, CoreError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|res| match res {
+            Ok(ch) if !ch.is_ascii() => Err(CoreError::InvalidChar),
+            other => other
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
+/// A iterator over overlapping windows of the chars of the content of a quoted string
+///
+/// It is created through `ContentChars::windows`.
+#[derive(Debug, Clone)]
+pub struct ContentCharsWindows<'a, Impl: GeneralQSSpec> {
+    inner: ContentChars<'a, Impl>,
+    size: usize,
+    window: Vec<char>,
+    done: bool
+}
+
+impl<'a, Impl> Iterator for ContentCharsWindows<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    type Item = Result<Vec<char>, CoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.window.len() == self.size {
+            self.window.remove(0);
+        }
+        while self.window.len() < self.size {
+            match self.inner.next() {
+                Some(Ok(ch)) => self.window.push(ch),
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Some(Err(err));
+                },
+                None => {
+                    self.done = true;
+                    return None;
+                }
+            }
+        }
+        Some(Ok(self.window.clone()))
+    }
+}
+
+/// A iterator over the chars of the content of a quoted string with a function applied to them
+///
+/// It is created through `ContentChars::map_chars`.
+#[derive(Clone)]
+pub struct MappedContentChars<'a, Impl: GeneralQSSpec, F> {
+    inner: ContentChars<'a, Impl>,
+    func: F
+}
+
+impl<'a, Impl, F> Iterator for MappedContentChars<'a, Impl, F>
+    where Impl: GeneralQSSpec, F: FnMut(char) -> char
+{
+    type Item = Result<char, CoreError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &mut self.func;
+        self.inner.next().map(|res| res.map(func))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A iterator over the chars of the content of a quoted string with a function applied to errors
+///
+/// It is created through `ContentChars::map_err`.
+#[derive(Clone)]
+pub struct MappedErrContentChars<'a, Impl: GeneralQSSpec, F> {
+    inner: ContentChars<'a, Impl>,
+    func: F
+}
+
+impl<'a, Impl, F, E> Iterator for MappedErrContentChars<'a, Impl, F>
+    where Impl: GeneralQSSpec, F: FnMut(CoreError) -> E
+{
+    type Item = Result<char, E>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let func = &mut self.func;
+        self.inner.next().map(|res| res.map_err(func))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A iterator over the chars of the content of two quoted strings one after another
+///
+/// It is created through `ContentChars::chain_decoded`.
+#[derive(Debug, Clone)]
+pub struct ChainedContentChars<'a, 'b, Impl: GeneralQSSpec> {
+    first: ContentChars<'a, Impl>,
+    second: ContentChars<'b, Impl>,
+    first_done: bool
+}
+
+impl<'a, 'b, Impl> Iterator for ChainedContentChars<'a, 'b, Impl>
+    where Impl: GeneralQSSpec
+{
+    type Item = Result<char, CoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.first_done {
+            match self.first.next() {
+                Some(res) => return Some(res),
+                None => self.first_done = true
+            }
+        }
+        self.second.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (second_min, second_max) = self.second.size_hint();
+        if self.first_done {
+            return (second_min, second_max);
+        }
+        let (first_min, first_max) = self.first.size_hint();
+        let max = match (first_max, second_max) {
+            (Some(first_max), Some(second_max)) => first_max.checked_add(second_max),
+            _ => None
+        };
+        (first_min.saturating_add(second_min), max)
+    }
+}
+
+/// A decoded char of the content of a quoted string
+///
+/// This newtype exists so that the decoded content can be summed up
+/// (or collected) into a `String` in generic contexts expecting
+/// `Sum`/`FromIterator`, which are not implemented for `char`.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::{ContentChars, DecodedChar};
+///
+/// let res = ContentChars::<TestSpec>::from_str("\"a\\\"b\"")
+///     .map(|res| res.map(DecodedChar))
+///     .sum::<Result<String, _>>();
+/// assert_eq!(res, Ok("a\"b".to_owned()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecodedChar(pub char);
+
+impl From<DecodedChar> for char {
+    fn from(ch: DecodedChar) -> char {
+        ch.0
+    }
+}
+
+impl Sum<DecodedChar> for String {
+    fn sum<I: Iterator<Item=DecodedChar>>(iter: I) -> String {
+        iter.collect()
+    }
+}
+
+impl FromIterator<DecodedChar> for String {
+    fn from_iter<I: IntoIterator<Item=DecodedChar>>(iter: I) -> String {
+        iter.into_iter().map(|ch| ch.0).collect()
+    }
+}
+
+/// creates a `ContentChars` iterator after validating the input
+///
+/// In difference to `ContentChars::from_str` the whole input has to be
+/// exactly one valid quoted string. If it is followed by additional
+/// input `CoreError::QuotedStringAlreadyEnded` is returned.
+///
+/// # Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::ContentChars;
+///
+/// let cc = ContentChars::<TestSpec>::try_from("\"a\\\"b\"").unwrap();
+/// assert_eq!(cc, "a\"b");
+/// assert!(ContentChars::<TestSpec>::try_from("\"a\"b").is_err());
+/// ```
+impl<'a, Impl> TryFrom<&'a str> for ContentChars<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    type Error = CoreError;
+
+    fn try_from(quoted: &'a str) -> Result<Self, CoreError> {
+        let parsed = parse::<Impl>(quoted).map_err(|(_idx, err)| err)?;
+        if !parsed.tail.is_empty() {
+            return Err(CoreError::QuotedStringAlreadyEnded);
+        }
+        Ok(ContentChars::from_str(quoted))
+    }
+}
 
 impl<'a, Impl> Iterator for ContentChars<'a, Impl>
     where Impl: GeneralQSSpec
@@ -102,17 +938,20 @@ impl<'a, Impl> Iterator for ContentChars<'a, Impl>
     type Item = Result<char, CoreError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.pre_validation_error {
+            return Some(Err(err));
+        }
         loop {
             if let Some(ch) = self.inner.next() {
                 let res = self.automaton.advance(PartialCodePoint::from_code_point(ch as u32));
                 match res {
-                    Err(e) => return Some(Err(e.into())),
+                    Err(e) => return Some(Err(e)),
                     Ok(true)  => return Some(Ok(ch)),
                     Ok(false) => {},
                 }
             } else {
                 match self.automaton.end() {
-                    Err(e) => return Some(Err(e.into())),
+                    Err(e) => return Some(Err(e)),
                     Ok(()) => return None
                 }
             }
@@ -123,20 +962,58 @@ impl<'a, Impl> Iterator for ContentChars<'a, Impl>
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.inner.size_hint()
     }
+
+    // `try_fold` can not be overridden on stable rust (the `Try` trait is unstable),
+    // but overriding `fold` inlines the decode loop for all internal iteration
+    // which doesn't short circuit (e.g. `count`, `for_each`, `collect` into a `String`)
+    fn fold<B, F>(self, init: B, mut func: F) -> B
+        where F: FnMut(B, Self::Item) -> B
+    {
+        let ContentChars { inner, mut automaton, pre_validation_error, .. } = self;
+        if let Some(err) = pre_validation_error {
+            return func(init, Err(err));
+        }
+        let mut acc = init;
+        for ch in inner {
+            match automaton.advance(PartialCodePoint::from_code_point(ch as u32)) {
+                Err(e) => acc = func(acc, Err(e)),
+                Ok(true) => acc = func(acc, Ok(ch)),
+                Ok(false) => {}
+            }
+        }
+        match automaton.end() {
+            Err(e) => func(acc, Err(e)),
+            Ok(()) => acc
+        }
+    }
 }
 
 
-impl<'a, Spec> PartialEq<str> for ContentChars<'a, Spec>
+/// shows the (remaining) decoded content, e.g. `ContentChars("a\"b")`
+///
+/// If the content can not be decoded `ContentChars(<decode error>)` is shown.
+impl<'a, Impl> fmt::Debug for ContentChars<'a, Impl>
+    where Impl: GeneralQSSpec
+{
+    fn fmt(&self, fter: &mut fmt::Formatter) -> fmt::Result {
+        match self.clone_and_collect() {
+            Ok(content) => fter.debug_tuple("ContentChars").field(&content).finish(),
+            Err(_) => fter.write_str("ContentChars(<decode error>)")
+        }
+    }
+}
+
+impl<Spec> PartialEq<str> for ContentChars<'_, Spec>
     where Spec: GeneralQSSpec
 {
 
     #[inline]
     fn eq(&self, other: &str) -> bool {
-        iter_eq(self.clone(), other.chars().map(|ch|Ok(ch)), |l,r|l==r)
+        iter_eq(self.clone(), other.chars().map(Ok), |l,r|l==r)
     }
 }
 
-impl<'a, 'b, Spec> PartialEq<ContentChars<'b, Spec>> for &'a str
+impl<'b, Spec> PartialEq<ContentChars<'b, Spec>> for &str
     where Spec: GeneralQSSpec
 {
     #[inline]
@@ -165,12 +1042,12 @@ impl<'a, 'b, Spec> PartialEq<ContentChars<'b, Spec>> for ContentChars<'a, Spec>
 
 
 
-impl<'a, Spec> AsciiCaseInsensitiveEq<str> for ContentChars<'a, Spec>
+impl<Spec> AsciiCaseInsensitiveEq<str> for ContentChars<'_, Spec>
     where Spec: GeneralQSSpec
 {
     #[inline]
     fn eq_ignore_ascii_case(&self, other: &str) -> bool {
-        iter_eq(self.clone(), other.chars().map(|ch|Ok(ch)), |l,r| l.eq_ignore_ascii_case(&r))
+        iter_eq(self.clone(), other.chars().map(Ok), |l,r| l.eq_ignore_ascii_case(&r))
     }
 }
 
@@ -183,7 +1060,7 @@ impl<'a, 'b, Spec> AsciiCaseInsensitiveEq<ContentChars<'b, Spec>> for ContentCha
     }
 }
 
-impl<'a, 'b, Spec> AsciiCaseInsensitiveEq<ContentChars<'b, Spec>> for &'a str
+impl<'b, Spec> AsciiCaseInsensitiveEq<ContentChars<'b, Spec>> for &str
     where Spec: GeneralQSSpec
 {
     #[inline]
@@ -203,6 +1080,49 @@ impl<'a, 'b, Spec> AsciiCaseInsensitiveEq<&'b str> for ContentChars<'a, Spec>
     }
 }
 
+impl<Spec> PartialEq<[u8]> for ContentChars<'_, Spec>
+    where Spec: GeneralQSSpec
+{
+    /// compares the content with the bytes interpreted as us-ascii
+    ///
+    /// If any byte is not us-ascii they are not equal.
+    #[inline]
+    fn eq(&self, other: &[u8]) -> bool {
+        iter_eq(self.clone(), ascii_bytes_as_chars(other), |l,r|l==r)
+    }
+}
+
+impl<'a, 'b, Spec> PartialEq<&'b [u8]> for ContentChars<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    #[inline]
+    fn eq(&self, other: &&'b [u8]) -> bool {
+        self == *other
+    }
+}
+
+impl<Spec> AsciiCaseInsensitiveEq<[u8]> for ContentChars<'_, Spec>
+    where Spec: GeneralQSSpec
+{
+    /// compares the content with the bytes interpreted as us-ascii
+    ///
+    /// If any byte is not us-ascii they are not equal.
+    #[inline]
+    fn eq_ignore_ascii_case(&self, other: &[u8]) -> bool {
+        iter_eq(self.clone(), ascii_bytes_as_chars(other), |l,r| l.eq_ignore_ascii_case(&r))
+    }
+}
+
+fn ascii_bytes_as_chars(bytes: &[u8]) -> impl Iterator<Item=Result<char, CoreError>> + '_ {
+    bytes.iter().map(|&bch| {
+        if bch.is_ascii() {
+            Ok(bch as char)
+        } else {
+            Err(CoreError::InvalidChar)
+        }
+    })
+}
+
 fn iter_eq<I1, I2, E, FN>(mut left: I1, mut right: I2, cmp: FN) -> bool
     where I1: Iterator<Item=Result<char, E>>,
           I2: Iterator<Item=Result<char, E>>, FN: Fn(char, char) -> bool
@@ -220,9 +1140,10 @@ fn iter_eq<I1, I2, E, FN>(mut left: I1, mut right: I2, cmp: FN) -> bool
 
 #[cfg(test)]
 mod test {
+    use std::convert::TryFrom;
     use test_utils::*;
     use error::CoreError;
-    use super::{ContentChars, AsciiCaseInsensitiveEq};
+    use super::{ContentChars, AsciiCaseInsensitiveEq, DecodedChar};
 
     #[test]
     fn missing_double_quoted() {
@@ -268,4 +1189,402 @@ mod test {
         let right = ContentChars::<TestSpec>::from_str(r#""aBc""#);
         assert!(left.eq_ignore_ascii_case(&right))
     }
+
+    #[test]
+    fn try_from_valid() {
+        let cc = ContentChars::<TestSpec>::try_from(r#""ab\"c""#).unwrap();
+        assert_eq!(cc, "ab\"c");
+    }
+
+    #[test]
+    fn try_from_invalid() {
+        let res = ContentChars::<TestSpec>::try_from("abc");
+        assert_eq!(res.unwrap_err(), CoreError::DoesNotStartWithDQuotes);
+    }
+
+    #[test]
+    fn try_from_with_tail() {
+        let res = ContentChars::<TestSpec>::try_from(r#""abc"; d"#);
+        assert_eq!(res.unwrap_err(), CoreError::QuotedStringAlreadyEnded);
+    }
+
+    #[test]
+    fn fold_behaves_like_next() {
+        let inputs = &["\"abc\"", "\"a\\\"b\n+-\nc\"", "abc", "\"abc", "\"a\"bc"];
+        for input in inputs {
+            let mut by_next = Vec::new();
+            for item in ContentChars::<TestSpec>::from_str(input) {
+                let is_err = item.is_err();
+                by_next.push(item);
+                if is_err { break }
+            }
+            let mut by_fold = ContentChars::<TestSpec>::from_str(input)
+                .fold(Vec::new(), |mut acc, item| { acc.push(item); acc });
+            if let Some(idx) = by_fold.iter().position(|item| item.is_err()) {
+                by_fold.truncate(idx + 1);
+            }
+            assert_eq!(by_next, by_fold);
+        }
+    }
+
+    #[test]
+    fn ascii_only() {
+        let res = ContentChars::<TestSpec>::from_str(r#""a\"b""#)
+            .ascii_only()
+            .collect::<Result<Vec<_>, _>>();
+        assert_eq!(res.unwrap().as_slice(), &['a', '"', 'b']);
+    }
+
+    #[test]
+    fn ascii_only_rejects_non_ascii() {
+        let mut iter = ContentChars::<AnyCharSpec>::from_str("\"aä\"").ascii_only();
+        assert_eq!(iter.next(), Some(Ok('a')));
+        assert_eq!(iter.next(), Some(Err(CoreError::InvalidChar)));
+    }
+
+    #[test]
+    fn into_string() {
+        let res = ContentChars::<TestSpec>::from_str(r#""a\"b""#).into_string();
+        assert_eq!(res, Ok("a\"b".to_owned()));
+        let res = ContentChars::<TestSpec>::from_str(r#""a\"b"#).into_string();
+        assert_eq!(res, Err(CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn into_string_lossy() {
+        let res = ContentChars::<TestSpec>::from_str(r#""a\"b""#).into_string_lossy('?');
+        assert_eq!(res, "a\"b");
+        let res = ContentChars::<TestSpec>::from_str("\"a\0b\"").into_string_lossy('?');
+        assert_eq!(res, "a?");
+    }
+
+    #[test]
+    fn zip_with_original() {
+        let res = ContentChars::<TestSpec>::from_str("\"a\\\\\n+-\nb\\c\"")
+            .zip_with_original()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(res, &[('a', "a"), ('\\', "\\\\"), ('b', "b"), ('c', "\\c")]);
+    }
+
+    #[test]
+    fn zip_with_original_error() {
+        let mut iter = ContentChars::<TestSpec>::from_str("\"a").zip_with_original();
+        assert_eq!(iter.next(), Some(Ok(('a', "a"))));
+        assert_eq!(iter.next(), Some(Err(CoreError::DoesNotEndWithDQuotes)));
+    }
+
+    #[test]
+    fn collect_bytes() {
+        let res = ContentChars::<TestSpec>::from_str(r#""a\"b""#).collect_bytes();
+        assert_eq!(res, Ok(b"a\"b".to_vec()));
+        let res = ContentChars::<AnyCharSpec>::from_str("\"aä\"").collect_bytes();
+        assert_eq!(res, Ok("aä".as_bytes().to_vec()));
+        let res = ContentChars::<TestSpec>::from_str("\"ab").collect_bytes();
+        assert_eq!(res, Err(CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn decoded_char_collect() {
+        let res = ContentChars::<TestSpec>::from_str(r#""a\"b""#)
+            .map(|res| res.map(DecodedChar))
+            .collect::<Result<String, _>>();
+        assert_eq!(res, Ok("a\"b".to_owned()));
+    }
+
+    #[test]
+    fn decoded_char_sum_error() {
+        let res = ContentChars::<TestSpec>::from_str("\"ab")
+            .map(|res| res.map(DecodedChar))
+            .sum::<Result<String, _>>();
+        assert_eq!(res, Err(CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn drain() {
+        let rest = ContentChars::<TestSpec>::from_str(r#""a\"bc""#).drain(0).unwrap();
+        assert_eq!(rest, "a\"bc");
+        let rest = ContentChars::<TestSpec>::from_str(r#""a\"bc""#).drain(4).unwrap();
+        assert_eq!(rest, "");
+        let mut rest = ContentChars::<TestSpec>::from_str(r#""a\"bc""#).drain(10).unwrap();
+        assert_eq!(rest.next(), None);
+    }
+
+    #[test]
+    fn drain_past_error() {
+        let res = ContentChars::<TestSpec>::from_str("\"a\0bc\"").drain(2);
+        assert_eq!(res.unwrap_err(), CoreError::InvalidChar);
+    }
+
+    #[test]
+    fn drain_error_in_skipped_part() {
+        let res = ContentChars::<TestSpec>::from_str("ab\"").drain(1);
+        assert_eq!(res.unwrap_err(), CoreError::DoesNotStartWithDQuotes);
+    }
+
+    #[test]
+    fn find_char() {
+        let cc = ContentChars::<TestSpec>::from_str("\"ab\\\"c\"");
+        assert_eq!(cc.find_char('a'), Some(Ok(0)));
+        assert_eq!(cc.find_char('"'), Some(Ok(2)));
+        assert_eq!(cc.find_char('d'), None);
+    }
+
+    #[test]
+    fn find_char_error_before_target() {
+        let cc = ContentChars::<TestSpec>::from_str("\"ab");
+        assert_eq!(cc.find_char('b'), Some(Ok(1)));
+        assert_eq!(cc.find_char('c'), Some(Err(CoreError::DoesNotEndWithDQuotes)));
+    }
+
+    #[test]
+    fn map_chars() {
+        let mut iter = ContentChars::<TestSpec>::from_str("\"ab\\\"c")
+            .map_chars(|ch| if ch == '"' { '\'' } else { ch });
+        assert_eq!(iter.next(), Some(Ok('a')));
+        assert_eq!(iter.next(), Some(Ok('b')));
+        assert_eq!(iter.next(), Some(Ok('\'')));
+        assert_eq!(iter.next(), Some(Ok('c')));
+        assert_eq!(iter.next(), Some(Err(CoreError::DoesNotEndWithDQuotes)));
+    }
+
+    #[test]
+    fn rewind_after_end() {
+        let mut cc = ContentChars::<TestSpec>::from_str("\"a\\\"\"");
+        assert_eq!(cc.by_ref().collect::<Result<String, _>>(), Ok("a\"".to_owned()));
+        assert_eq!(cc.next(), None);
+        cc.rewind();
+        assert_eq!(cc.next(), Some(Ok('a')));
+        assert_eq!(cc.next(), Some(Ok('"')));
+        assert_eq!(cc.next(), None);
+    }
+
+    #[test]
+    fn rewind_after_error() {
+        let mut cc = ContentChars::<TestSpec>::from_str("\"a\0\"");
+        assert_eq!(cc.next(), Some(Ok('a')));
+        assert!(cc.next().unwrap().is_err());
+        cc.rewind();
+        assert_eq!(cc.next(), Some(Ok('a')));
+    }
+
+    #[test]
+    fn map_err() {
+        let mut iter = ContentChars::<TestSpec>::from_str("\"a")
+            .map_err(|err| err.id());
+        assert_eq!(iter.next(), Some(Ok('a')));
+        assert_eq!(iter.next(), Some(Err(CoreError::DoesNotEndWithDQuotes.id())));
+    }
+
+    #[test]
+    fn eq_bytes() {
+        let cc = ContentChars::<TestSpec>::from_str("\"a\\\"b\"");
+        assert!(cc == b"a\"b"[..]);
+        assert!(cc != b"a\"bc"[..]);
+        assert!(cc != b"a\""[..]);
+        assert!(cc != b"A\"B"[..]);
+        assert!(cc.eq_ignore_ascii_case(&b"A\"B"[..]));
+    }
+
+    #[test]
+    fn eq_bytes_non_ascii() {
+        let cc = ContentChars::<AnyCharSpec>::from_str("\"ä\"");
+        assert!(cc != "ä".as_bytes());
+        assert!(!cc.eq_ignore_ascii_case("ä".as_bytes()));
+    }
+
+    #[test]
+    fn zip_positions() {
+        let mut iter = ContentChars::<TestSpec>::from_str("\"a\n+-\n\\\\b").zip_positions();
+        assert_eq!(iter.next(), Some(Ok((0, 'a'))));
+        assert_eq!(iter.next(), Some(Ok((1, '\\'))));
+        assert_eq!(iter.next(), Some(Ok((2, 'b'))));
+        assert_eq!(iter.next(), Some(Err(CoreError::DoesNotEndWithDQuotes)));
+    }
+
+    #[test]
+    fn count_escaped_pairs() {
+        let mut cc = ContentChars::<TestSpec>::from_str("\"\\a\\\\\"");
+        assert_eq!(cc.count_escaped_pairs(), Ok(2));
+        assert_eq!(cc.next(), Some(Ok('a')));
+        assert_eq!(cc.count_escaped_pairs(), Ok(1));
+        let cc = ContentChars::<TestSpec>::from_str("\"\\a");
+        assert_eq!(cc.count_escaped_pairs(), Err(CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn windows() {
+        let mut iter = ContentChars::<TestSpec>::from_str("\"abc\"").windows(3);
+        assert_eq!(iter.next(), Some(Ok(vec!['a', 'b', 'c'])));
+        assert_eq!(iter.next(), None);
+        let mut iter = ContentChars::<TestSpec>::from_str("\"ab\"").windows(3);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn windows_error() {
+        let mut iter = ContentChars::<TestSpec>::from_str("\"abc").windows(2);
+        assert_eq!(iter.next(), Some(Ok(vec!['a', 'b'])));
+        assert_eq!(iter.next(), Some(Ok(vec!['b', 'c'])));
+        assert_eq!(iter.next(), Some(Err(CoreError::DoesNotEndWithDQuotes)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn windows_of_zero_size() {
+        ContentChars::<TestSpec>::from_str("\"abc\"").windows(0);
+    }
+
+    #[test]
+    fn byte_len_decoded() {
+        let len = ContentChars::<TestSpec>::from_str("\"a\\\"\n+-\nb\"").byte_len_decoded();
+        assert_eq!(len, Ok(3));
+        let len = ContentChars::<AnyCharSpec>::from_str("\"aä\"").byte_len_decoded();
+        assert_eq!(len, Ok(3));
+        let len = ContentChars::<TestSpec>::from_str("\"ab").byte_len_decoded();
+        assert_eq!(len, Err(CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn eq_str_ascii_ci() {
+        let cc = ContentChars::<TestSpec>::from_str("\"aB\\\"c\"");
+        assert!(cc.eq_str_ascii_ci("Ab\"C"));
+        assert!(!cc.eq_str_ascii_ci("Ab\"Cd"));
+        let cc = ContentChars::<TestSpec>::from_str("\"aB");
+        assert!(!cc.eq_str_ascii_ci("ab"));
+    }
+
+    #[test]
+    fn clone_and_collect() {
+        let mut cc = ContentChars::<TestSpec>::from_str("\"a\\\"bc\"");
+        assert_eq!(cc.next(), Some(Ok('a')));
+        assert_eq!(cc.clone_and_collect(), Ok("\"bc".to_owned()));
+        assert_eq!(cc.next(), Some(Ok('"')));
+        let cc = ContentChars::<TestSpec>::from_str("\"abc");
+        assert_eq!(cc.clone_and_collect(), Err(CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn chain_decoded() {
+        let first = ContentChars::<TestSpec>::from_str("\"a\\\"\"");
+        let second = ContentChars::<TestSpec>::from_str("\"b\n\nc\"");
+        let joined = first.chain_decoded(second).collect::<Result<String, _>>();
+        assert_eq!(joined, Ok("a\"bc".to_owned()));
+    }
+
+    #[test]
+    fn chain_decoded_passes_through_errors() {
+        let first = ContentChars::<TestSpec>::from_str("\"a\"");
+        let second = ContentChars::<TestSpec>::from_str("\"b");
+        let mut iter = first.chain_decoded(second);
+        assert_eq!(iter.next(), Some(Ok('a')));
+        assert_eq!(iter.next(), Some(Ok('b')));
+        assert_eq!(iter.next(), Some(Err(CoreError::DoesNotEndWithDQuotes)));
+    }
+
+    #[test]
+    fn take_n() {
+        let cc = ContentChars::<TestSpec>::from_str("\"a\\\"bc\"");
+        assert_eq!(cc.clone().take_n(0), Ok(String::new()));
+        assert_eq!(cc.clone().take_n(3), Ok("a\"b".to_owned()));
+        assert_eq!(cc.take_n(10), Ok("a\"bc".to_owned()));
+        let cc = ContentChars::<TestSpec>::from_str("\"a");
+        assert_eq!(cc.take_n(2), Err(CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn drop_n() {
+        let cc = ContentChars::<TestSpec>::from_str("\"a\\\"bc\"");
+        assert_eq!(cc.clone().drop_n(2).unwrap(), "bc");
+        assert_eq!(cc.drop_n(10).unwrap(), "");
+        let cc = ContentChars::<TestSpec>::from_str("a\"");
+        assert_eq!(cc.drop_n(1).unwrap_err(), CoreError::DoesNotStartWithDQuotes);
+    }
+
+    #[test]
+    fn is_empty() {
+        let mut cc = ContentChars::<TestSpec>::from_str("\"a\n+-\n\"");
+        assert!(!cc.is_empty());
+        assert_eq!(cc.next(), Some(Ok('a')));
+        assert!(cc.is_empty());
+        assert_eq!(cc.next(), None);
+    }
+
+    #[test]
+    fn is_empty_with_error() {
+        let cc = ContentChars::<TestSpec>::from_str("\"");
+        assert!(!cc.is_empty());
+        let cc = ContentChars::<TestSpec>::from_str("\"\" a");
+        assert!(!cc.is_empty());
+    }
+
+    #[test]
+    fn into_reader_read_to_string() {
+        use std::io::Read;
+        let mut out = String::new();
+        ContentChars::<AnyCharSpec>::from_str("\"a\\\"äb\"").into_reader()
+            .read_to_string(&mut out)
+            .unwrap();
+        assert_eq!(out, "a\"äb");
+    }
+
+    #[test]
+    fn into_reader_lines() {
+        use std::io::BufRead;
+        let lines = ContentChars::<AnyCharSpec>::from_str("\"a\nb\\\nc\"").into_reader()
+            .lines()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(lines, &["a", "b", "c"]);
+    }
+
+    #[test]
+    fn into_reader_error_after_partial_read() {
+        use std::io::{Read, ErrorKind};
+        let mut reader = ContentChars::<TestSpec>::from_str("\"ab").into_reader();
+        let mut buf = [0; 8];
+        assert_eq!(reader.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], b"ab");
+        let err = reader.read(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.into_inner().unwrap().downcast_ref::<CoreError>(),
+                   Some(&CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn into_reader_error() {
+        use std::io::{Read, ErrorKind};
+        let mut out = String::new();
+        let err = ContentChars::<TestSpec>::from_str("\"ab").into_reader()
+            .read_to_string(&mut out)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.into_inner().unwrap().downcast_ref::<CoreError>(),
+                   Some(&CoreError::DoesNotEndWithDQuotes));
+    }
+
+    #[test]
+    fn debug_shows_decoded_content() {
+        let mut cc = ContentChars::<TestSpec>::from_str("\"a\\\"b\n\nc\"");
+        assert_eq!(format!("{:?}", cc), "ContentChars(\"a\\\"bc\")");
+        cc.next();
+        assert_eq!(format!("{:?}", cc), "ContentChars(\"\\\"bc\")");
+    }
+
+    #[test]
+    fn debug_with_decode_error() {
+        let cc = ContentChars::<TestSpec>::from_str("\"ab");
+        assert_eq!(format!("{:?}", cc), "ContentChars(<decode error>)");
+    }
+
+    #[test]
+    fn from_str_calls_pre_validate() {
+        assert_eq!(ContentChars::<MaxLenSpec>::from_str("\"abc\""), "abc");
+        let mut cc = ContentChars::<MaxLenSpec>::from_str("\"abcdefghi\"");
+        assert_eq!(cc.count_escaped_pairs(), Err(CoreError::LimitExceeded));
+        assert_eq!(cc.clone().collect::<Result<String, _>>(), Err(CoreError::LimitExceeded));
+        assert_eq!(cc.clone().zip_with_original().next(), Some(Err(CoreError::LimitExceeded)));
+        assert_eq!(cc.next(), Some(Err(CoreError::LimitExceeded)));
+    }
 }
@@ -10,22 +10,56 @@
 //TODO add new/updated documentation
 //#![warn(missing_docs)]
 
+#[cfg(feature = "serde")]
+extern crate serde;
 
 
-pub use iter::{ContentChars, AsciiCaseInsensitiveEq};
+
+pub use iter::{
+    ContentChars, ContentCharsAsciiOnly, ContentCharsWithOriginal, ContentCharsWithPositions,
+    ContentCharsWindows, ContentCharsReader, MappedContentChars, MappedErrContentChars,
+    ChainedContentChars, DecodedChar, AsciiCaseInsensitiveEq
+};
 pub use unquote::{
-    to_content, strip_dquotes
+    to_content, to_content_ascii_only, unquote_in_place, unquote_pairs_only,
+    append_content_to, strip_dquotes, strip_dquotes_and_trim,
+    has_quoted_pairs, quoted_string_hash, cmp_content
 };
 pub use quote::{
-    quote, quote_if_needed
+    quote, quote_bytes, from_unquoted, from_content_unchecked, quote_append, quote_to_writer,
+    WritableQuotedString, quote_display, quote_if_needed, quote_if_needed_with_flag,
+    encode_as_token_or_quoted, encode_parameter_value, write_quoted_string_header,
+    write_unquoted_or_quoted, append_quoted_pair, append_qtext
+};
+pub use parse::{
+    validate, validate_utf8, validate_symmetric, validate_all, validate_any, validate_none,
+    validate_content_pair_count, validate_length_range, validate_no_leading_trailing_ws,
+    validate_parameter_name,
+    validation_report, ValidationReport,
+    parse, parse_complete, parse_opt, parse_with_validator, Parsed,
+    parse_with_config, ParseConfig,
+    parse_lenient, LenientParsed, LenientFix,
+    parse_structured_value, parse_header_field, HeaderField, StreamingParser,
+    parse_folded_header, FoldedParsed,
+    parse_comment, ParsedComment, parse_as_content_type, ContentType,
+    parse_header_param, HeaderParam,
+    iter_quoted_strings, parse_result_iter, QuotedStringIter
 };
-pub use parse::{validate, parse, Parsed};
+pub use prevalidated::PrevalidatedStr;
+#[doc(hidden)]
+pub use macros::{__quoted_string_len, __quote_to_array};
+/// the spec used in the examples and tests, re-exported for use in tests of dependent crates
+#[cfg(feature = "testing")]
+pub use test_utils::{TestSpec, TestParsingImpl, TestUnquotedValidator};
 
 
+#[macro_use]
+mod macros;
 pub mod spec;
 mod iter;
 mod unquote;
 mod quote;
 mod parse;
+mod prevalidated;
 pub mod error;
 pub mod test_utils;
@@ -0,0 +1,156 @@
+/// quotes the given input using `Spec` panicking if it can not be quoted
+///
+/// This is a shorthand for `quote::<Spec>(input).unwrap()` (with a
+/// better panic message) meant to make test code and the initialization
+/// of values known to be quotable less noisy.
+///
+/// # Panics
+///
+/// If `quote::<Spec>(input)` returns an error, e.g. because the input
+/// contains a char which can not be represented in a quoted string.
+///
+/// # Example
+/// ```
+/// #[macro_use]
+/// extern crate quoted_string;
+/// //use your own Spec in practise
+/// use quoted_string::test_utils::TestSpec;
+///
+/// # fn main() {
+/// let quoted = try_quoted_string!(TestSpec, "a\"b");
+/// assert_eq!(quoted, "\"a\\\"b\"");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! try_quoted_string {
+    ($spec:ty, $input:expr) => ({
+        let input: &str = $input;
+        match $crate::quote::<$spec>(input) {
+            Ok(quoted) => quoted,
+            Err(err) => panic!("can not quote {:?}: {}", input, err)
+        }
+    });
+}
+
+/// quotes the given string literal at compile time returning a `&'static str`
+///
+/// The input is quoted using the rules of `test_utils::TestSpec`, i.e. all
+/// chars from `' '` to `'~'` are valid and `'"'` and `'\\'` are escaped using
+/// a quoted-pair. If the input contains any other char (e.g. `'\t'` or non
+/// us-ascii chars) compilation fails. The input has to be a constant
+/// expression, use `try_quoted_string!` for runtime values or other specs.
+///
+/// # Example
+/// ```
+/// #[macro_use]
+/// extern crate quoted_string;
+///
+/// const BOUNDARY: &str = quoted_string!("=_part \"1\"");
+///
+/// # fn main() {
+/// assert_eq!(BOUNDARY, "\"=_part \\\"1\\\"\"");
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate quoted_string;
+///
+/// # fn main() {
+/// let _ = quoted_string!("tab\tis invalid");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! quoted_string {
+    ($input:expr) => ({
+        const INPUT: &str = $input;
+        const LEN: usize = $crate::__quoted_string_len(INPUT);
+        const QUOTED: &[u8; LEN] = &$crate::__quote_to_array::<LEN>(INPUT);
+        const OUT: &str = match ::std::str::from_utf8(QUOTED) {
+            Ok(out) => out,
+            Err(_) => panic!("[BUG] quoted_string! produced invalid utf-8")
+        };
+        OUT
+    });
+}
+
+/// returns the length `input` has once quoted by `quoted_string!`
+///
+/// # Panics
+///
+/// If `input` contains a char which can not be represented, which makes
+/// the compilation fail if called in a const context.
+#[doc(hidden)]
+pub const fn __quoted_string_len(input: &str) -> usize {
+    let bytes = input.as_bytes();
+    let mut len = 2;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        len += match bytes[idx] {
+            b'"' | b'\\' => 2,
+            b' '..=b'~' => 1,
+            _ => panic!("quoted_string!: input contains a char which can not be represented")
+        };
+        idx += 1;
+    }
+    len
+}
+
+/// quotes `input` into an array of exactly the length returned by `__quoted_string_len`
+#[doc(hidden)]
+pub const fn __quote_to_array<const LEN: usize>(input: &str) -> [u8; LEN] {
+    let bytes = input.as_bytes();
+    let mut out = [b'"'; LEN];
+    let mut out_idx = 1;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let bch = bytes[idx];
+        if bch == b'"' || bch == b'\\' {
+            out[out_idx] = b'\\';
+            out_idx += 1;
+        }
+        out[out_idx] = bch;
+        out_idx += 1;
+        idx += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use test_utils::TestSpec;
+    use quote::quote;
+
+    #[test]
+    fn quoted_string_quotes_at_compile_time() {
+        const QUOTED: &str = quoted_string!("a\"b\\c");
+        assert_eq!(QUOTED, "\"a\\\"b\\\\c\"");
+    }
+
+    #[test]
+    fn quoted_string_matches_quote() {
+        assert_eq!(quoted_string!(""), quote::<TestSpec>("").unwrap());
+        assert_eq!(
+            quoted_string!("Quarterly Report (final).pdf"),
+            quote::<TestSpec>("Quarterly Report (final).pdf").unwrap()
+        );
+        assert_eq!(quoted_string!("\\\""), quote::<TestSpec>("\\\"").unwrap());
+    }
+
+    #[test]
+    fn quotes_input() {
+        assert_eq!(try_quoted_string!(TestSpec, "simple"), "\"simple\"");
+    }
+
+    #[test]
+    fn works_with_non_literals() {
+        let input = String::from("a\\b");
+        assert_eq!(try_quoted_string!(TestSpec, &input), "\"a\\\\b\"");
+    }
+
+    #[test]
+    #[should_panic]
+    fn panics_if_not_quotable() {
+        try_quoted_string!(TestSpec, "a\u{0}b");
+    }
+}
@@ -1,5 +1,11 @@
-use spec::{ScanAutomaton, GeneralQSSpec,  PartialCodePoint};
+use std::borrow::Cow;
+use std::marker::PhantomData;
+use std::str::{self, Utf8Error};
+
+use spec::{ScanAutomaton, GeneralQSSpec, PartialCodePoint, State, WithoutQuotingValidator};
 use error::CoreError;
+use unquote::to_content;
+use iter::ContentChars;
 
 /// validates if input is a valid quoted-string
 ///
@@ -22,8 +28,363 @@ pub fn validate<Spec: GeneralQSSpec>(input: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// checks if parsing `input` results in a quoted string which is exactly `input`
+///
+/// In difference to `validate` this differentiates between input not
+/// starting with a valid quoted string (`Err`) and input starting with a
+/// valid quoted string followed by additional input (`Ok(false)`). This
+/// is e.g. useful in tests of serializers which should output exactly
+/// one quoted string.
+///
+/// # Error
+///
+/// the errors of `parse` are returned if `input` does not start with a
+/// valid quoted string
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validate_symmetric;
+///
+/// assert_eq!(validate_symmetric::<TestSpec>("\"a b\""), Ok(true));
+/// assert_eq!(validate_symmetric::<TestSpec>("\"a\" b"), Ok(false));
+/// assert!(validate_symmetric::<TestSpec>("a b").is_err());
+/// ```
+pub fn validate_symmetric<Spec: GeneralQSSpec>(input: &str) -> Result<bool, (usize, CoreError)> {
+    let parsed = parse::<Spec>(input)?;
+    Ok(parsed.quoted_string == input)
+}
+
+/// validates if the bytes are valid utf-8 and a valid quoted-string
+///
+/// This is meant as entry point for bytes e.g. received from the network.
+/// Like `validate` it requires the whole input to be one quoted-string.
+///
+/// # Error
+///
+/// A `Utf8Error` is returned if the input is not valid utf-8, valid utf-8
+/// which is not a valid quoted-string results in `Ok(false)`.
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validate_utf8;
+///
+/// assert_eq!(validate_utf8::<TestSpec>(b"\"quoted string\""), Ok(true));
+/// assert_eq!(validate_utf8::<TestSpec>(b"\"not closed"), Ok(false));
+/// assert!(validate_utf8::<TestSpec>(b"\"\xff\"").is_err());
+/// ```
+pub fn validate_utf8<Spec: GeneralQSSpec>(input: &[u8]) -> Result<bool, Utf8Error> {
+    let input = str::from_utf8(input)?;
+    Ok(validate::<Spec>(input))
+}
+
+/// validates each of the inputs returning if they are valid in the same order
+///
+/// Like `validate` it requires each input to be one quoted-string.
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validate_all;
+///
+/// let res = validate_all::<TestSpec, _>(vec!["\"a\"", "b", "\"c\""]);
+/// assert_eq!(res, &[true, false, true]);
+/// ```
+pub fn validate_all<'a, Spec, I>(inputs: I) -> Vec<bool>
+    where Spec: GeneralQSSpec,
+          I: IntoIterator<Item=&'a str>
+{
+    inputs.into_iter()
+        .map(validate::<Spec>)
+        .collect()
+}
+
+/// returns true if at least one of the inputs is a valid quoted-string
+///
+/// It stops validating at the first valid input.
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validate_any;
+///
+/// assert!(validate_any::<TestSpec, _>(vec!["a", "\"b\""]));
+/// assert!(!validate_any::<TestSpec, _>(vec!["a", "b"]));
+/// ```
+pub fn validate_any<'a, Spec, I>(inputs: I) -> bool
+    where Spec: GeneralQSSpec,
+          I: IntoIterator<Item=&'a str>
+{
+    inputs.into_iter().any(validate::<Spec>)
+}
+
+/// returns true if none of the inputs is a valid quoted-string
+///
+/// It stops validating at the first valid input.
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validate_none;
+///
+/// assert!(validate_none::<TestSpec, _>(vec!["a", "b"]));
+/// assert!(!validate_none::<TestSpec, _>(vec!["a", "\"b\""]));
+/// ```
+pub fn validate_none<'a, Spec, I>(inputs: I) -> bool
+    where Spec: GeneralQSSpec,
+          I: IntoIterator<Item=&'a str>
+{
+    !validate_any::<Spec, _>(inputs)
+}
+
+/// validates if input is a valid quoted-string returning the number of quoted-pairs in it
+///
+/// Like `validate` it requires the whole input to be one quoted-string.
+///
+/// # Error
+///
+/// a error and the byte index where it was triggered is returned if the input
+/// is not a valid quoted-string.
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validate_content_pair_count;
+///
+/// assert_eq!(validate_content_pair_count::<TestSpec>("\"simple\""), Ok(0));
+/// assert_eq!(validate_content_pair_count::<TestSpec>("\"\\\"a\\b\""), Ok(2));
+/// assert!(validate_content_pair_count::<TestSpec>("\"a\"b").is_err());
+/// ```
+pub fn validate_content_pair_count<Spec: GeneralQSSpec>(input: &str)
+    -> Result<usize, (usize, CoreError)>
+{
+    Spec::pre_validate(input)
+        .map_err(|err| (0, err))?;
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    let mut count = 0;
+    for (idx, bch) in input.bytes().enumerate() {
+        automaton.advance(PartialCodePoint::from_utf8_byte(bch))
+            .map_err(|err| (idx, err))?;
+        if automaton.state() == State::QPStart {
+            count += 1;
+        }
+    }
+    automaton.end()
+        .map_err(|err| (input.len(), err))?;
+    Ok(count)
+}
+
+/// validates if `name` is a valid parameter name (e.g. of a MIME parameter)
+///
+/// Parameter names are not quoted strings but normally follow a `token`
+/// production, a new (default) instance of `Token` is used to validate
+/// it (i.e. `Token` decides what is a valid token, like for
+/// `encode_as_token_or_quoted`). The name has to contain at least one char.
+///
+/// # Example
+///
+/// ```
+/// // use your own token validator
+/// use quoted_string::spec::AsciiWordValidator;
+/// use quoted_string::validate_parameter_name;
+///
+/// assert!(validate_parameter_name::<AsciiWordValidator>("charset"));
+/// assert!(!validate_parameter_name::<AsciiWordValidator>("char set"));
+/// assert!(!validate_parameter_name::<AsciiWordValidator>(""));
+/// ```
+pub fn validate_parameter_name<Token>(name: &str) -> bool
+    where Token: WithoutQuotingValidator + Default
+{
+    is_complete_token::<Token>(name)
+}
+
+/// validates if input is a valid quoted-string with a content length in `[min, max]`
+///
+/// Like `validate` it requires the whole input to be one quoted-string.
+/// The length is the number of chars of the decoded content (i.e. without
+/// the surrounding `'"'`, quoted-pairs count as one char and non-semantic
+/// parts are not counted). Validation is stopped as soon as the length
+/// exceeds `max`.
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validate_length_range;
+///
+/// assert!(validate_length_range::<TestSpec>("\"a\\\"b\"", 1, 3));
+/// assert!(!validate_length_range::<TestSpec>("\"\"", 1, 3));
+/// assert!(!validate_length_range::<TestSpec>("\"abcd\"", 1, 3));
+/// ```
+pub fn validate_length_range<Spec: GeneralQSSpec>(input: &str, min: usize, max: usize) -> bool {
+    if Spec::pre_validate(input).is_err() {
+        return false;
+    }
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    let mut count = 0;
+    for bch in input.bytes() {
+        match automaton.advance(PartialCodePoint::from_utf8_byte(bch)) {
+            Err(_) => return false,
+            // only count the first byte of each emitted code point
+            Ok(true) if bch & 0xC0 != 0x80 => {
+                count += 1;
+                if count > max {
+                    return false;
+                }
+            },
+            Ok(_) => {}
+        }
+    }
+    automaton.end().is_ok() && count >= min
+}
+
+/// validates if input is a valid quoted-string without leading or trailing white space
+///
+/// Like `validate` it requires the whole input to be one quoted-string.
+/// Additionally the first and last char of the decoded content must not
+/// be white space (as defined by `char::is_whitespace`). Non-semantic
+/// parts (e.g. folding white space) are not part of the decoded content
+/// and as such are ignored, but white space in quoted-pairs is not.
+/// Some protocols, e.g. SIP, require this even if their quoted-string
+/// grammar allows it.
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validate_no_leading_trailing_ws;
+///
+/// assert!(validate_no_leading_trailing_ws::<TestSpec>("\"a b\""));
+/// assert!(!validate_no_leading_trailing_ws::<TestSpec>("\" a b\""));
+/// assert!(!validate_no_leading_trailing_ws::<TestSpec>("\"a b \""));
+/// ```
+pub fn validate_no_leading_trailing_ws<Spec: GeneralQSSpec>(input: &str) -> bool {
+    if !validate::<Spec>(input) {
+        return false;
+    }
+    let mut first = None;
+    let mut last = None;
+    for res in ContentChars::<Spec>::from_str(input) {
+        let ch = match res {
+            Ok(ch) => ch,
+            Err(_) => return false
+        };
+        first.get_or_insert(ch);
+        last = Some(ch);
+    }
+    !first.into_iter().chain(last).any(char::is_whitespace)
+}
+
+/// the result of `validation_report`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidationReport {
+    /// all found issues with the byte index where they were triggered
+    pub issues: Vec<(usize, CoreError)>,
+    /// the number of chars of the decoded content, if the input is valid
+    pub content_len: Option<usize>
+}
+
+impl ValidationReport {
+    /// returns true if no issues were found
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// validates the input collecting all issues instead of stopping at the first
+///
+/// Like `validate` it requires the whole input to be one quoted-string.
+/// Chars which are invalid at their position (e.g. `CoreError::InvalidChar`)
+/// are reported and then skipped, so that following issues are found,
+/// too. Issues which make it impossible to continue (e.g. the input not
+/// starting with `'"'` or continuing after the closing `'"'`) end the
+/// validation.
+///
+/// # Example
+///
+/// ```
+/// // use your own spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::validation_report;
+/// use quoted_string::error::CoreError;
+///
+/// let report = validation_report::<TestSpec>("\"a\\\"b\"");
+/// assert!(report.is_valid());
+/// assert_eq!(report.content_len, Some(3));
+///
+/// let report = validation_report::<TestSpec>("\"a\0b\0");
+/// assert_eq!(report.issues, &[
+///     (2, CoreError::InvalidChar),
+///     (4, CoreError::InvalidChar),
+///     (5, CoreError::DoesNotEndWithDQuotes)
+/// ]);
+/// assert_eq!(report.content_len, None);
+/// ```
+pub fn validation_report<Spec: GeneralQSSpec>(input: &str) -> ValidationReport {
+    let mut issues = Vec::new();
+    if let Err(err) = Spec::pre_validate(input) {
+        issues.push((0, err));
+    }
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    let mut count = 0;
+    let mut can_continue = true;
+    // bytes are fed like in `parse` so that issues are reported at the same
+    // index `parse` would fail at
+    let mut idx = 0;
+    while idx < input.len() {
+        let before = automaton.clone();
+        match automaton.advance(PartialCodePoint::from_utf8_byte(input.as_bytes()[idx])) {
+            // only count the first byte of each emitted char
+            Ok(true) => if input.is_char_boundary(idx) { count += 1 },
+            Ok(false) => {},
+            Err(err) => {
+                can_continue = err != CoreError::DoesNotStartWithDQuotes
+                    && err != CoreError::QuotedStringAlreadyEnded;
+                issues.push((idx, err));
+                if !can_continue {
+                    break;
+                }
+                // skip the (rest of the) char as if it wasn't there
+                automaton = before;
+                idx += 1;
+                while !input.is_char_boundary(idx) {
+                    idx += 1;
+                }
+                continue;
+            }
+        }
+        idx += 1;
+    }
+    if can_continue {
+        if let Err(err) = automaton.end() {
+            issues.push((input.len(), err));
+        }
+    }
+    let content_len = if issues.is_empty() { Some(count) } else { None };
+    ValidationReport { issues, content_len }
+}
+
 /// the result of successfully parsing a quoted string
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+///
+/// Both fields borrow from the input passed to `parse` (i.e. have the
+/// lifetime `'a` of the input), as it only consists of two shared references
+/// it is `Copy` and cheap to pass around by value.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub struct Parsed<'a> {
     /// the parsed quoted string
     pub quoted_string: &'a str,
@@ -31,6 +392,30 @@ pub struct Parsed<'a> {
     pub tail: &'a str
 }
 
+impl<'a> Parsed<'a> {
+
+    /// returns the content of the parsed quoted string
+    ///
+    /// This calls `to_content` with the parsed `quoted_string`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // use your own Spec
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::parse;
+    ///
+    /// let content = parse::<TestSpec>("\"a\\\"b\"; tail")
+    ///     .unwrap()
+    ///     .try_into_content::<TestSpec>()
+    ///     .unwrap();
+    /// assert_eq!(content, "a\"b");
+    /// ```
+    pub fn try_into_content<Spec: GeneralQSSpec>(self) -> Result<Cow<'a, str>, CoreError> {
+        to_content::<Spec>(self.quoted_string)
+    }
+}
+
 /// parse a quoted string starting at the begin of `input` but possible ending earlier
 ///
 /// To check if the whole string is a quoted-string (an nothing more) you have to
@@ -55,17 +440,89 @@ pub struct Parsed<'a> {
 /// });
 /// ```
 ///
-pub fn parse<Impl: GeneralQSSpec>(input: &str) -> Result<Parsed, (usize, CoreError)> {
-    let mut automaton = ScanAutomaton::<Impl::Parsing>::new();
+pub fn parse<Impl: GeneralQSSpec>(input: &str) -> Result<Parsed<'_>, (usize, CoreError)> {
+    scan_quoted_string::<Impl, _>(input, NoLimits)
+}
+
+/// limits checked by `scan_quoted_string` while scanning
+///
+/// `scan_quoted_string` is generic over it, so for `NoLimits` (used by
+/// `parse`) the checks are no-ops which are compiled away.
+trait ScanLimits {
+    /// checks the byte at `idx` before it is fed into the automaton
+    fn check_byte(&self, idx: usize) -> Result<(), CoreError>;
+    /// checks the byte `bch` after the automaton emitted it
+    fn check_emitted(&mut self, bch: u8) -> Result<(), CoreError>;
+}
+
+struct NoLimits;
+
+impl ScanLimits for NoLimits {
+    #[inline(always)]
+    fn check_byte(&self, _idx: usize) -> Result<(), CoreError> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn check_emitted(&mut self, _bch: u8) -> Result<(), CoreError> {
+        Ok(())
+    }
+}
+
+struct ConfigLimits {
+    max_byte_len: usize,
+    max_content_chars: usize,
+    content_chars: usize
+}
+
+impl ScanLimits for ConfigLimits {
+    #[inline]
+    fn check_byte(&self, idx: usize) -> Result<(), CoreError> {
+        if idx >= self.max_byte_len {
+            Err(CoreError::LimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn check_emitted(&mut self, bch: u8) -> Result<(), CoreError> {
+        // only count the first byte of each emitted code point
+        if bch & 0xC0 != 0x80 {
+            self.content_chars += 1;
+            if self.content_chars > self.max_content_chars {
+                return Err(CoreError::LimitExceeded);
+            }
+        }
+        Ok(())
+    }
+}
+
+// the scanning loop of `parse` and `parse_with_config`
+fn scan_quoted_string<Spec, Limits>(input: &str, mut limits: Limits)
+    -> Result<Parsed<'_>, (usize, CoreError)>
+    where Spec: GeneralQSSpec,
+          Limits: ScanLimits
+{
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
 
     for (idx, bch) in input.bytes().enumerate() {
-        automaton.advance(PartialCodePoint::from_utf8_byte(bch))
-            .map_err(|err| (idx, err.into()))?;
+        limits.check_byte(idx)
+            .map_err(|err| (idx, err))?;
+        let emit = automaton.advance(PartialCodePoint::from_utf8_byte(bch))
+            .map_err(|err| (idx, err))?;
+        if emit {
+            limits.check_emitted(bch)
+                .map_err(|err| (idx, err))?;
+        }
 
         if automaton.did_end() {
+            //idx+1: idx is the idx of the ending '"' which has a byte len of 1
+            let quoted_string = &input[0..idx + 1];
+            Spec::pre_validate(quoted_string)
+                .map_err(|err| (0, err))?;
             return Ok(Parsed {
-                //idx+1: idx is the idx of the ending '"' which has a byte len of 1
-                quoted_string: &input[0..idx + 1],
+                quoted_string,
                 tail: &input[idx + 1..]
             })
         }
@@ -76,101 +533,1811 @@ pub fn parse<Impl: GeneralQSSpec>(input: &str) -> Result<Parsed, (usize, CoreErr
         Ok(_) =>
             panic!("[BUG] automaton.did_end() == false but automaton.end() does not trigger error"),
         Err(err) => {
-            Err((input.len(), err.into()))
+            Err((input.len(), err))
         }
     }
 }
 
+/// limits used by `parse_with_config`
+///
+/// A limit of `None` means there is no limit, which is also the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ParseConfig {
+    /// the maximal number of chars of the decoded content
+    pub max_content_chars: Option<usize>,
+    /// the maximal byte length of the quoted string (including the surrounding `'"'`)
+    pub max_byte_len: Option<usize>
+}
 
-#[cfg(test)]
-mod test {
-
-    mod parse {
-        use test_utils::*;
-        use error::CoreError;
-        use super::super::parse;
+/// parse a quoted string like `parse` but enforce the limits from `config`
+///
+/// This is meant for input e.g. received from the network where
+/// pathologically long quoted strings should be rejected. Parsing
+/// is aborted as soon as a limit is exceeded. Only the quoted string
+/// itself is limited, the `tail` can have any length.
+///
+/// # Error
+///
+/// If a limit is exceeded `CoreError::LimitExceeded` is returned with
+/// the index of the byte which exceeded it, else the errors of `parse`
+/// are returned.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::{parse_with_config, ParseConfig};
+/// use quoted_string::error::CoreError;
+///
+/// let config = ParseConfig { max_content_chars: Some(3), ..Default::default() };
+/// assert!(parse_with_config::<TestSpec>("\"abc\"; tail", &config).is_ok());
+/// assert_eq!(
+///     parse_with_config::<TestSpec>("\"abcd\"", &config),
+///     Err((4, CoreError::LimitExceeded))
+/// );
+/// ```
+pub fn parse_with_config<'a, Spec: GeneralQSSpec>(input: &'a str, config: &ParseConfig)
+    -> Result<Parsed<'a>, (usize, CoreError)>
+{
+    let limits = ConfigLimits {
+        max_byte_len: config.max_byte_len.unwrap_or(usize::MAX),
+        max_content_chars: config.max_content_chars.unwrap_or(usize::MAX),
+        content_chars: 0
+    };
+    scan_quoted_string::<Spec, _>(input, limits)
+}
 
-        #[test]
-        fn parse_simple() {
-            let parsed = parse::<TestSpec>("\"simple\"").unwrap();
-            assert_eq!(parsed.quoted_string, "\"simple\"");
-            assert_eq!(parsed.tail, "");
-        }
+/// parses a quoted string requiring it to span the whole input
+///
+/// This is like `parse` but fails if the tail would not be empty,
+/// so the returned `Parsed` always has an empty `tail`.
+///
+/// # Error
+///
+/// Returns the errors of `parse`, or `CoreError::QuotedStringAlreadyEnded` with
+/// the index of the first byte after the quoted string if the input continues
+/// after the quoted string.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::parse_complete;
+/// use quoted_string::error::CoreError;
+///
+/// let parsed = parse_complete::<TestSpec>("\"simple\"").unwrap();
+/// assert_eq!(parsed.quoted_string, "\"simple\"");
+/// assert_eq!(parse_complete::<TestSpec>("\"simple\" "), Err((8, CoreError::QuotedStringAlreadyEnded)));
+/// ```
+pub fn parse_complete<Spec: GeneralQSSpec>(input: &str) -> Result<Parsed<'_>, (usize, CoreError)> {
+    let parsed = parse::<Spec>(input)?;
+    if parsed.tail.is_empty() {
+        Ok(parsed)
+    } else {
+        Err((parsed.quoted_string.len(), CoreError::QuotedStringAlreadyEnded))
+    }
+}
 
-        #[test]
-        fn parse_with_tail() {
-            let parsed = parse::<TestSpec>("\"simple\"; abc").unwrap();
-            assert_eq!(parsed.quoted_string, "\"simple\"");
-            assert_eq!(parsed.tail, "; abc");
-        }
+/// parses a quoted string returning `None` instead of an error
+///
+/// This is `parse(input).ok()`, useful if the quoted string is an
+/// optional part of a larger grammar where the reason why the input
+/// is not a quoted string doesn't matter.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::parse_opt;
+///
+/// let parsed = parse_opt::<TestSpec>("\"simple\"; abc").unwrap();
+/// assert_eq!(parsed.tail, "; abc");
+/// assert_eq!(parse_opt::<TestSpec>("simple"), None);
+/// ```
+#[inline]
+pub fn parse_opt<Spec: GeneralQSSpec>(input: &str) -> Option<Parsed<'_>> {
+    parse::<Spec>(input).ok()
+}
 
-        #[test]
-        fn parse_with_quoted_pairs() {
-            let parsed = parse::<TestSpec>("\"si\\\"m\\\\ple\"").unwrap();
-            assert_eq!(parsed.quoted_string, "\"si\\\"m\\\\ple\"");
-            assert_eq!(parsed.tail, "");
-        }
+/// parses a quoted string like `parse` and then validates it with `post_validate`
+///
+/// This allows enforcing additional constraints not covered by `Spec`
+/// (e.g. the content must not be empty).
+///
+/// # Error
+///
+/// Returns the errors of `parse` or `CoreError::PostValidationFailed` with the
+/// index of the first byte after the quoted string if `post_validate` returns
+/// false.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::parse_with_validator;
+/// use quoted_string::error::CoreError;
+///
+/// let not_empty = |parsed: quoted_string::Parsed| parsed.quoted_string.len() > 2;
+/// assert!(parse_with_validator::<TestSpec, _>("\"a\"", not_empty).is_ok());
+/// assert_eq!(
+///     parse_with_validator::<TestSpec, _>("\"\"; a", not_empty),
+///     Err((2, CoreError::PostValidationFailed))
+/// );
+/// ```
+pub fn parse_with_validator<Spec, F>(input: &str, post_validate: F)
+    -> Result<Parsed<'_>, (usize, CoreError)>
+    where Spec: GeneralQSSpec,
+          F: FnOnce(Parsed) -> bool
+{
+    let parsed = parse::<Spec>(input)?;
+    if post_validate(parsed) {
+        Ok(parsed)
+    } else {
+        Err((parsed.quoted_string.len(), CoreError::PostValidationFailed))
+    }
+}
 
-        #[test]
-        fn parse_with_unnecessary_quoted_pairs() {
-            let parsed = parse::<TestSpec>("\"sim\\p\\le\"").unwrap();
-            assert_eq!(parsed.quoted_string, "\"sim\\p\\le\"");
-            assert_eq!(parsed.tail, "");
-        }
+/// a deviation from the grammar fixed by `parse_lenient`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LenientFix {
+    /// the closing `'"'` was missing and has been added
+    MissingClosingQuote,
+    /// the input ended with a `'\\'` starting a quoted-pair, it was
+    /// treated as literal `'\\'` (i.e. replaced with the quoted-pair `\\`)
+    TrailingEscape
+}
 
-        #[test]
-        fn reject_missing_quoted() {
-            let res = parse::<TestSpec>("simple");
-            assert_eq!(res, Err((0, CoreError::DoesNotStartWithDQuotes)));
-        }
+/// the result of `parse_lenient`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LenientParsed<'a> {
+    /// the parsed quoted string, owned if any fixes had to be applied
+    pub quoted_string: Cow<'a, str>,
+    /// the rest of the input string, not parsed
+    pub tail: &'a str,
+    /// the fixes applied to the input (in the order they were applied)
+    pub fixes: Vec<LenientFix>
+}
+
+/// parses a quoted string like `parse` but fixes some common mistakes
+///
+/// Some real world producers emit quoted strings missing the closing
+/// `'"'` or ending in a stray `'\\'`. If the input is such a quoted
+/// string (which spans the whole input) it is fixed and the applied
+/// fixes are returned. All other errors are returned like by `parse`.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::{parse_lenient, LenientFix};
+///
+/// let parsed = parse_lenient::<TestSpec>("\"abc\\").unwrap();
+/// assert_eq!(parsed.quoted_string, "\"abc\\\\\"");
+/// assert_eq!(parsed.fixes, &[LenientFix::TrailingEscape, LenientFix::MissingClosingQuote]);
+///
+/// let parsed = parse_lenient::<TestSpec>("\"abc\"; tail").unwrap();
+/// assert_eq!(parsed.quoted_string, "\"abc\"");
+/// assert!(parsed.fixes.is_empty());
+/// ```
+pub fn parse_lenient<Spec: GeneralQSSpec>(input: &str) -> Result<LenientParsed<'_>, (usize, CoreError)> {
+    let error = match parse::<Spec>(input) {
+        Ok(Parsed { quoted_string, tail }) => return Ok(LenientParsed {
+            quoted_string: Cow::Borrowed(quoted_string),
+            tail,
+            fixes: Vec::new()
+        }),
+        // the error was not caused by the quoted string not being closed
+        Err((idx, err)) if idx != input.len() => return Err((idx, err)),
+        Err(error) => error
+    };
+
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    for bch in input.bytes() {
+        automaton.advance(PartialCodePoint::from_utf8_byte(bch))
+            .map_err(|_| error)?;
+    }
+
+    let mut fixed = String::with_capacity(input.len() + 2);
+    fixed.push_str(input);
+    let mut fixes = Vec::new();
+    match automaton.state() {
+        State::Normal => {},
+        State::QPStart => {
+            fixed.push('\\');
+            fixes.push(LenientFix::TrailingEscape);
+        },
+        _ => return Err(error)
+    }
+    fixed.push('"');
+    fixes.push(LenientFix::MissingClosingQuote);
+
+    if !validate::<Spec>(&fixed) {
+        return Err(error);
+    }
+    Ok(LenientParsed { quoted_string: Cow::Owned(fixed), tail: "", fixes })
+}
+
+/// parses the quoted string value of a `Name: "value"` header line
+///
+/// This skips the field name and the `':'`, skips CFWS (white space
+/// including folding `"\r\n"` and comments) around the value and
+/// delegates to `parse`. CFWS following the value is not part of the
+/// returned `tail`.
+///
+/// # Error
+///
+/// If the line does not contain a `':'` `CoreError::MissingColon` is returned,
+/// else the errors of `parse_comment` and `parse` are returned with the index
+/// being relative to the start of `header_line`.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::parse_structured_value;
+///
+/// let parsed = parse_structured_value::<TestSpec>("Content-Description: \"My document\"\r\n")
+///     .unwrap();
+/// assert_eq!(parsed.quoted_string, "\"My document\"");
+/// assert_eq!(parsed.tail, "");
+/// ```
+pub fn parse_structured_value<Spec: GeneralQSSpec>(header_line: &str)
+    -> Result<Parsed<'_>, (usize, CoreError)>
+{
+    let colon_idx = header_line.find(':')
+        .ok_or((header_line.len(), CoreError::MissingColon))?;
+    let offset = skip_cfws::<Spec>(header_line, colon_idx + 1)?;
+    let Parsed { quoted_string, tail } = parse::<Spec>(&header_line[offset..])
+        .map_err(|(idx, err)| (offset + idx, err))?;
+    let tail_offset = header_line.len() - tail.len();
+    let tail = &header_line[skip_cfws::<Spec>(header_line, tail_offset)?..];
+    Ok(Parsed { quoted_string, tail })
+}
+
+fn is_header_ws(ch: char) -> bool {
+    ch == ' ' || ch == '\t' || ch == '\r' || ch == '\n'
+}
+
+/// returns the index of the first char at or after `idx` which is not part of CFWS
+fn skip_cfws<Spec: GeneralQSSpec>(input: &str, idx: usize) -> Result<usize, (usize, CoreError)> {
+    let mut idx = skip_header_ws(input, idx);
+    while input[idx..].starts_with('(') {
+        let parsed = parse_comment::<Spec>(&input[idx..])
+            .map_err(|(err_idx, err)| (idx + err_idx, err))?;
+        idx = skip_header_ws(input, input.len() - parsed.tail.len());
+    }
+    Ok(idx)
+}
+
+/// the result of `parse_folded_header`
+///
+/// In difference to `Parsed` it owns the quoted string and tail,
+/// as unfolding the input requires creating a new string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FoldedParsed {
+    /// the parsed (unfolded) quoted string
+    pub quoted_string: String,
+    /// the rest of the (unfolded) input, not parsed
+    pub tail: String
+}
+
+/// unfolds the given header lines and then parses a quoted string starting at the begin of them
+///
+/// All lines except the first are continuation lines, for each of them
+/// the leading white space is stripped and it is joined with the previous
+/// line using a single space. Line terminators (`"\r\n"` or `"\n"`) at
+/// the end of the lines are removed.
+///
+/// # Error
+///
+/// The errors of `parse` are returned, the index is relative to the
+/// unfolded input.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::parse_folded_header;
+///
+/// let parsed = parse_folded_header::<TestSpec>(&["\"a long\r\n", "    value\"; tail"]).unwrap();
+/// assert_eq!(parsed.quoted_string, "\"a long value\"");
+/// assert_eq!(parsed.tail, "; tail");
+/// ```
+pub fn parse_folded_header<Spec: GeneralQSSpec>(lines: &[&str])
+    -> Result<FoldedParsed, (usize, CoreError)>
+{
+    let mut unfolded = String::with_capacity(lines.iter().map(|line| line.len() + 1).sum());
+    for (idx, line) in lines.iter().enumerate() {
+        let line = line.trim_end_matches(&['\r', '\n'][..]);
+        if idx == 0 {
+            unfolded.push_str(line);
+        } else {
+            unfolded.push(' ');
+            unfolded.push_str(line.trim_start_matches(&[' ', '\t'][..]));
+        }
+    }
+    let parsed = parse::<Spec>(&unfolded)?;
+    Ok(FoldedParsed {
+        quoted_string: parsed.quoted_string.to_owned(),
+        tail: parsed.tail.to_owned()
+    })
+}
+
+/// a header field as returned by `parse_header_field`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HeaderField<'a> {
+    /// the name of the header field
+    pub name: &'a str,
+    /// the unfolded value of the header field without comments
+    pub value: Cow<'a, str>
+}
+
+/// parses a `Name: value` header line unfolding the value and removing comments
+///
+/// The returned value is unfolded (i.e. `"\r\n"` followed by white space is
+/// removed), comments (outside of quoted strings) are replaced by a single
+/// space and leading and trailing white space is stripped. All quoted strings
+/// in the value are validated using `Spec` but kept as they are (except for
+/// being unfolded).
+///
+/// # Error
+///
+/// If the line does not contain a `':'` `CoreError::MissingColon` is returned,
+/// if the name is empty `CoreError::ZeroSizedValue`, if the name contains a
+/// char which is not printable us-ascii (RFC 5322 `ftext`) `CoreError::InvalidChar`,
+/// if a `"\r\n"` is not followed by white space (and is not at the end of the
+/// line) `CoreError::UnexpectedTrailingInput`, if a comment is invalid the error
+/// of `parse_comment` and if a quoted string is invalid the error of `parse`.
+/// The index is always relative to the start of `header_line`.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::parse_header_field;
+///
+/// let field = parse_header_field::<TestSpec>(
+///     "Content-Type: text/plain; (the charset)\r\n charset=\"utf-8\"\r\n"
+/// ).unwrap();
+/// assert_eq!(field.name, "Content-Type");
+/// assert_eq!(field.value, "text/plain;   charset=\"utf-8\"");
+/// ```
+pub fn parse_header_field<Spec: GeneralQSSpec>(header_line: &str)
+    -> Result<HeaderField<'_>, (usize, CoreError)>
+{
+    let colon_idx = header_line.find(':')
+        .ok_or((header_line.len(), CoreError::MissingColon))?;
+    let name = header_line[..colon_idx].trim_end_matches(&[' ', '\t'][..]);
+    if name.is_empty() {
+        return Err((0, CoreError::ZeroSizedValue));
+    }
+    if let Some(err_idx) = name.bytes().position(|bch| !(33..=126).contains(&bch)) {
+        return Err((err_idx, CoreError::InvalidChar));
+    }
+
+    let mut value = String::with_capacity(header_line.len() - colon_idx);
+    let mut idx = colon_idx + 1;
+    while idx < header_line.len() {
+        let rest = &header_line[idx..];
+        if rest.starts_with("\r\n ") || rest.starts_with("\r\n\t") {
+            idx += 2;
+        } else if rest.starts_with("\r\n") && rest.len() > 2 {
+            // a CRLF not followed by white space ends the header field
+            return Err((idx, CoreError::UnexpectedTrailingInput));
+        } else if rest.starts_with('"') {
+            let parsed = parse::<Spec>(rest)
+                .map_err(|(err_idx, err)| (idx + err_idx, err))?;
+            push_unfolded(&mut value, parsed.quoted_string);
+            idx += parsed.quoted_string.len();
+        } else if rest.starts_with('(') {
+            let parsed = parse_comment::<Spec>(rest)
+                .map_err(|(err_idx, err)| (idx + err_idx, err))?;
+            idx += parsed.comment.len();
+            value.push(' ');
+        } else {
+            let ch = rest.chars().next().expect("[BUG] idx < len but no char left");
+            value.push(ch);
+            idx += ch.len_utf8();
+        }
+    }
+
+    let trimmed = value.trim_matches(is_header_ws);
+    let raw_value = header_line[colon_idx + 1..].trim_matches(is_header_ws);
+    let value = if trimmed == raw_value {
+        Cow::Borrowed(raw_value)
+    } else {
+        Cow::Owned(trimmed.to_owned())
+    };
+    Ok(HeaderField { name, value })
+}
+
+fn push_unfolded(out: &mut String, input: &str) {
+    let mut last_end = 0;
+    for (idx, _) in input.match_indices("\r\n") {
+        let next = input.as_bytes().get(idx + 2);
+        if next == Some(&b' ') || next == Some(&b'\t') {
+            out.push_str(&input[last_end..idx]);
+            last_end = idx + 2;
+        }
+    }
+    out.push_str(&input[last_end..]);
+}
+
+/// the result of successfully parsing a comment
+///
+/// Like `Parsed` it only consists of two shared references into the input.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ParsedComment<'a> {
+    /// the parsed comment including the surrounding `'('` and `')'`
+    pub comment: &'a str,
+    /// the rest of the input string, not parsed
+    pub tail: &'a str
+}
+
+/// parses a (RFC 5322) comment at the start of input
+///
+/// A comment starts with `'('` and ends with the matching `')'`, it can
+/// contain nested comments and quoted-pairs. Quoted-pairs are validated
+/// using `Spec::quoted_pair_char_is_valid`, i.e. the same way they are
+/// validated in quoted strings, all other chars are accepted as they are.
+///
+/// # Error
+///
+/// If the input does not start with `'('` `CoreError::DoesNotStartWithParenthesis`
+/// is returned, if the comment is not closed `CoreError::UnclosedComment` and
+/// if a char which can not be quoted follows a `'\\'` `CoreError::UnquoteableCharQuoted`.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::parse_comment;
+///
+/// let parsed = parse_comment::<TestSpec>("(a (nested\\) one)) tail").unwrap();
+/// assert_eq!(parsed.comment, "(a (nested\\) one))");
+/// assert_eq!(parsed.tail, " tail");
+/// ```
+pub fn parse_comment<Spec: GeneralQSSpec>(input: &str)
+    -> Result<ParsedComment<'_>, (usize, CoreError)>
+{
+    if !input.starts_with('(') {
+        return Err((0, CoreError::DoesNotStartWithParenthesis));
+    }
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for (idx, ch) in input.char_indices() {
+        if escaped {
+            if !Spec::quoted_pair_char_is_valid(ch) {
+                return Err((idx, CoreError::UnquoteableCharQuoted));
+            }
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    let (comment, tail) = input.split_at(idx + 1);
+                    return Ok(ParsedComment { comment, tail });
+                }
+            },
+            _ => {}
+        }
+    }
+    Err((input.len(), CoreError::UnclosedComment))
+}
+
+/// the media type and the parameters (name and value) returned by `parse_as_content_type`
+pub type ContentType<'a> = (Cow<'a, str>, Vec<(Cow<'a, str>, Cow<'a, str>)>);
+
+/// parses a Content-Type header value into the media type and its parameters
+///
+/// The value is expected to be of the form `type/subtype; name=value; ...`
+/// where `type` and `subtype` have to be tokens accepted by `Token` and each
+/// parameter is parsed with `parse_header_param`, i.e. names and unquoted
+/// values have to be tokens accepted by `Token` and quoted string values are
+/// validated using `Spec` and decoded. White space around the media type,
+/// names and values is ignored and a trailing `';'` is tolerated. The media
+/// type and the parameter names are not normalized, e.g. they are not lower
+/// cased.
+///
+/// # Error
+///
+/// If the type or subtype is empty `CoreError::ZeroSizedValue` is returned,
+/// if it is not a valid token or they are not separated by `'/'`
+/// `CoreError::InvalidChar`, if the media type or a parameter is followed by
+/// anything but white space or `';'` `CoreError::UnexpectedTrailingInput` and
+/// if a parameter is invalid the error of `parse_header_param`. The index is
+/// always relative to the start of `header`.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec and token validator
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::spec::AsciiWordValidator;
+/// use quoted_string::parse_as_content_type;
+///
+/// let (media_type, params) = parse_as_content_type::<TestSpec, AsciiWordValidator>(
+///     "text/html; charset=\"UTF-8\"; boundary=\"--a\\\"b\""
+/// ).unwrap();
+/// assert_eq!(media_type, "text/html");
+/// assert_eq!(params[0].0, "charset");
+/// assert_eq!(params[0].1, "UTF-8");
+/// assert_eq!(params[1].0, "boundary");
+/// assert_eq!(params[1].1, "--a\"b");
+/// ```
+pub fn parse_as_content_type<Spec, Token>(header: &str)
+    -> Result<ContentType<'_>, (usize, CoreError)>
+    where Spec: GeneralQSSpec,
+          Token: WithoutQuotingValidator + Default
+{
+    let media_start = skip_header_ws(header, 0);
+    let type_len = token_len::<Token>(&header[media_start..])
+        .map_err(|(idx, err)| (media_start + idx, err))?;
+    let slash_idx = media_start + type_len;
+    if !header[slash_idx..].starts_with('/') {
+        return Err((slash_idx, CoreError::InvalidChar));
+    }
+    let subtype_start = slash_idx + 1;
+    let subtype_len = token_len::<Token>(&header[subtype_start..])
+        .map_err(|(idx, err)| (subtype_start + idx, err))?;
+    let media_end = subtype_start + subtype_len;
+    let media_type = &header[media_start..media_end];
+
+    let mut params = Vec::new();
+    let mut rest = &header[media_end..];
+    loop {
+        let offset = header.len() - rest.len();
+        let sep_idx = skip_header_ws(rest, 0);
+        if sep_idx == rest.len() {
+            break;
+        }
+        if !rest[sep_idx..].starts_with(';') {
+            return Err((offset + sep_idx, CoreError::UnexpectedTrailingInput));
+        }
+        // a trailing ';' is tolerated
+        if skip_header_ws(rest, sep_idx + 1) == rest.len() {
+            break;
+        }
+        let ((name, value), tail) = parse_header_param::<Spec, Token>(&rest[sep_idx..])
+            .map_err(|(idx, err)| (offset + sep_idx + idx, err))?;
+        params.push((Cow::Borrowed(name), value));
+        rest = tail;
+    }
+    Ok((Cow::Borrowed(media_type), params))
+}
+
+/// the name and the (decoded) value of a parameter returned by `parse_header_param`
+pub type HeaderParam<'a> = (&'a str, Cow<'a, str>);
+
+/// parses a single `; name=value` parameter at the start of `input`
+///
+/// A leading `';'` and white space around it are optional. Both the name
+/// and an unquoted value are read as long as a new (default) instance of
+/// `Token` accepts them (like for `validate_parameter_name`). A quoted
+/// value is parsed using `Spec` and decoded. White space around the `'='`
+/// is allowed. Returns the name, the (decoded) value and the rest of the
+/// input.
+///
+/// # Error
+///
+/// If the name or an unquoted value is empty `CoreError::ZeroSizedValue` is
+/// returned, if `Token` does not accept it as complete token `CoreError::InvalidChar`,
+/// if the name is not followed by `'='` `CoreError::MissingEqualsSign` and
+/// if a quoted value is invalid the error of `parse`. The index is always
+/// relative to the start of `input`.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec and token validator
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::spec::AsciiWordValidator;
+/// use quoted_string::parse_header_param;
+///
+/// let ((name, value), tail) =
+///     parse_header_param::<TestSpec, AsciiWordValidator>("; title=\"a \\\"b\\\"\"; x=y").unwrap();
+/// assert_eq!(name, "title");
+/// assert_eq!(value, "a \"b\"");
+/// assert_eq!(tail, "; x=y");
+/// ```
+pub fn parse_header_param<Spec, Token>(input: &str)
+    -> Result<(HeaderParam<'_>, &str), (usize, CoreError)>
+    where Spec: GeneralQSSpec,
+          Token: WithoutQuotingValidator + Default
+{
+    let mut name_start = skip_header_ws(input, 0);
+    if input[name_start..].starts_with(';') {
+        name_start = skip_header_ws(input, name_start + 1);
+    }
+    let name_len = token_len::<Token>(&input[name_start..])
+        .map_err(|(idx, err)| (name_start + idx, err))?;
+    let name = &input[name_start..name_start + name_len];
+
+    let eq_idx = skip_header_ws(input, name_start + name_len);
+    if !input[eq_idx..].starts_with('=') {
+        return Err((eq_idx, CoreError::MissingEqualsSign));
+    }
+
+    let value_start = skip_header_ws(input, eq_idx + 1);
+    let rest = &input[value_start..];
+    if rest.starts_with('"') {
+        let parsed = parse::<Spec>(rest)
+            .map_err(|(idx, err)| (value_start + idx, err))?;
+        let value = to_content::<Spec>(parsed.quoted_string)
+            .map_err(|err| (value_start, err))?;
+        Ok(((name, value), parsed.tail))
+    } else {
+        let value_len = token_len::<Token>(rest)
+            .map_err(|(idx, err)| (value_start + idx, err))?;
+        Ok(((name, Cow::Borrowed(&rest[..value_len])), &rest[value_len..]))
+    }
+}
+
+/// returns the byte length of the (non empty) token `input` starts with
+fn token_len<Token>(input: &str) -> Result<usize, (usize, CoreError)>
+    where Token: WithoutQuotingValidator + Default
+{
+    let mut validator = Token::default();
+    let len = validator.validate_sequence(input).err().unwrap_or(input.len());
+    if len == 0 {
+        Err((0, CoreError::ZeroSizedValue))
+    } else if !validator.end() {
+        Err((len, CoreError::InvalidChar))
+    } else {
+        Ok(len)
+    }
+}
+
+/// returns true if all of `input` is a (non empty) token accepted by a new `Token`
+pub(crate) fn is_complete_token<Token>(input: &str) -> bool
+    where Token: WithoutQuotingValidator + Default
+{
+    token_len::<Token>(input) == Ok(input.len())
+}
+
+/// returns the index of the first non white space char at or after `idx`
+fn skip_header_ws(input: &str, idx: usize) -> usize {
+    let rest = &input[idx..];
+    idx + (rest.len() - rest.trim_start_matches(is_header_ws).len())
+}
+
+/// returns an iterator over all quoted strings in `text`
+///
+/// See `QuotedStringIter` for more details.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::iter_quoted_strings;
+///
+/// let found = iter_quoted_strings::<TestSpec>("a=\"b c\", d=\"e\\\"f\"")
+///     .map(|res| res.unwrap().quoted_string)
+///     .collect::<Vec<_>>();
+/// assert_eq!(found, &["\"b c\"", "\"e\\\"f\""]);
+/// ```
+pub fn iter_quoted_strings<Spec: GeneralQSSpec>(text: &str) -> QuotedStringIter<'_, Spec> {
+    QuotedStringIter {
+        rest: text,
+        offset: 0,
+        _spec: PhantomData
+    }
+}
+
+/// returns an iterator over the raw slices of all well formed quoted strings in `text`
+///
+/// This is `iter_quoted_strings` yielding only the `quoted_string` field
+/// of each `Parsed` and skipping malformed quoted strings. The quoted
+/// strings are not decoded, which makes it useful e.g. for logging.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::parse_result_iter;
+///
+/// let found = parse_result_iter::<TestSpec>("a=\"b\", c=\"e\", d=\"\0\"")
+///     .collect::<Vec<_>>();
+/// assert_eq!(found, &["\"b\"", "\"e\""]);
+/// ```
+pub fn parse_result_iter<Spec: GeneralQSSpec>(text: &str) -> impl Iterator<Item=&str> {
+    iter_quoted_strings::<Spec>(text)
+        .filter_map(|res| res.ok())
+        .map(|parsed| parsed.quoted_string)
+}
+
+/// iterator over all quoted strings in a text
+///
+/// Everything starting with a `'"'` is parsed as quoted string,
+/// all text between quoted strings is skipped. If a quoted string
+/// is malformed the error (with the byte index relative to the start
+/// of the text) is returned and scanning continues after the end of
+/// the malformed quoted string (i.e. the next `'"'` which is not part of
+/// a quoted-pair), so all well formed quoted strings after it can still
+/// be found.
+#[derive(Debug, Clone)]
+pub struct QuotedStringIter<'a, Spec: GeneralQSSpec> {
+    rest: &'a str,
+    offset: usize,
+    _spec: PhantomData<Spec>
+}
+
+impl<'a, Spec> QuotedStringIter<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    /// the part of the text which was not yet scanned
+    pub fn rest(&self) -> &'a str {
+        self.rest
+    }
+}
+
+impl<'a, Spec> Iterator for QuotedStringIter<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    type Item = Result<Parsed<'a>, (usize, CoreError)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = match self.rest.find('"') {
+            Some(start) => start,
+            None => {
+                self.offset += self.rest.len();
+                self.rest = "";
+                return None;
+            }
+        };
+        let candidate = &self.rest[start..];
+        let candidate_offset = self.offset + start;
+        match parse::<Spec>(candidate) {
+            Ok(parsed) => {
+                self.offset = candidate_offset + parsed.quoted_string.len();
+                self.rest = parsed.tail;
+                Some(Ok(parsed))
+            },
+            Err((idx, err)) => {
+                let continue_from = malformed_quoted_string_len(candidate);
+                self.offset = candidate_offset + continue_from;
+                self.rest = &candidate[continue_from..];
+                Some(Err((candidate_offset + idx, err)))
+            }
+        }
+    }
+}
+
+/// returns the byte length of the malformed quoted string `input` starts with
+///
+/// The end is the first `'"'` after the opening one which is not part of a
+/// quoted-pair (or the end of the input), independent of whether the chars
+/// in between are valid.
+fn malformed_quoted_string_len(input: &str) -> usize {
+    let mut escaped = false;
+    for (idx, bch) in input.bytes().enumerate().skip(1) {
+        if escaped {
+            escaped = false;
+        } else if bch == b'\\' {
+            escaped = true;
+        } else if bch == b'"' {
+            return idx + 1;
+        }
+    }
+    input.len()
+}
+
+/// parser for a quoted string which is received in multiple chunks
+///
+/// Like `parse` it parses a quoted string starting at the begin of the
+/// input but the input is passed in through (multiple) calls to `push_chunk`.
+/// As it works on bytes chunks can split the input anywhere, even inside of
+/// a utf-8 code point.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::StreamingParser;
+///
+/// let mut parser = StreamingParser::<TestSpec>::new();
+/// assert_eq!(parser.push_chunk(b"\"ab\\"), Ok(None));
+/// assert_eq!(parser.push_chunk(b"\"c\"; tail"), Ok(Some(7)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamingParser<Spec: GeneralQSSpec> {
+    automaton: ScanAutomaton<Spec::Parsing>,
+    consumed: usize
+}
+
+impl<Spec> StreamingParser<Spec>
+    where Spec: GeneralQSSpec
+{
+    pub fn new() -> Self {
+        StreamingParser {
+            automaton: ScanAutomaton::new(),
+            consumed: 0
+        }
+    }
+
+    /// feeds the next chunk of input into the parser
+    ///
+    /// Returns `Some(n)` once the closing `'"'` was found, where `n` is the byte
+    /// length of the whole quoted string (including the closing `'"'`). Bytes
+    /// after it are not consumed, calling `push_chunk` again after the end was
+    /// found will not consume any more bytes and return `Some(n)` again.
+    ///
+    /// Returns `None` if more input is needed.
+    ///
+    /// # Error
+    ///
+    /// a error and the byte index (relative to the start of the first chunk) where
+    /// it was triggered is returned if the input is not a valid quoted-string.
+    pub fn push_chunk(&mut self, chunk: &[u8]) -> Result<Option<usize>, (usize, CoreError)> {
+        if self.automaton.did_end() {
+            return Ok(Some(self.consumed));
+        }
+        for bch in chunk {
+            self.automaton.advance(PartialCodePoint::from_utf8_byte(*bch))
+                .map_err(|err| (self.consumed, err))?;
+            self.consumed += 1;
+
+            if self.automaton.did_end() {
+                return Ok(Some(self.consumed));
+            }
+        }
+        Ok(None)
+    }
+
+    /// returns true if the closing `'"'` was found
+    pub fn did_end(&self) -> bool {
+        self.automaton.did_end()
+    }
+
+    /// the number of bytes consumed so far
+    pub fn bytes_consumed(&self) -> usize {
+        self.consumed
+    }
+}
+
+impl<Spec> Default for StreamingParser<Spec>
+    where Spec: GeneralQSSpec
+{
+    fn default() -> Self {
+        StreamingParser::new()
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+
+    mod parse {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{parse, Parsed};
+
+        #[test]
+        fn parse_simple() {
+            let parsed = parse::<TestSpec>("\"simple\"").unwrap();
+            assert_eq!(parsed.quoted_string, "\"simple\"");
+            assert_eq!(parsed.tail, "");
+        }
+
+        #[test]
+        fn parse_with_tail() {
+            let parsed = parse::<TestSpec>("\"simple\"; abc").unwrap();
+            assert_eq!(parsed.quoted_string, "\"simple\"");
+            assert_eq!(parsed.tail, "; abc");
+        }
+
+        #[test]
+        fn parse_with_quoted_pairs() {
+            let parsed = parse::<TestSpec>("\"si\\\"m\\\\ple\"").unwrap();
+            assert_eq!(parsed.quoted_string, "\"si\\\"m\\\\ple\"");
+            assert_eq!(parsed.tail, "");
+        }
+
+        #[test]
+        fn parse_with_unnecessary_quoted_pairs() {
+            let parsed = parse::<TestSpec>("\"sim\\p\\le\"").unwrap();
+            assert_eq!(parsed.quoted_string, "\"sim\\p\\le\"");
+            assert_eq!(parsed.tail, "");
+        }
+
+        #[test]
+        fn reject_missing_quoted() {
+            let res = parse::<TestSpec>("simple");
+            assert_eq!(res, Err((0, CoreError::DoesNotStartWithDQuotes)));
+        }
+
+        #[test]
+        fn reject_tailing_escape() {
+            let res = parse::<TestSpec>("\"simple\\\"");
+            assert_eq!(res, Err((9, CoreError::DoesNotEndWithDQuotes)));
+        }
+
+        #[test]
+        fn reject_unquoted_quotable() {
+            let res = parse::<TestSpec>("\"simp\\\0le\"");
+            assert_eq!(res, Err((6, CoreError::UnquoteableCharQuoted)));
+        }
+
+        #[test]
+        fn reject_missing_closing_dquotes() {
+            let res = parse::<TestSpec>("\"simple");
+            assert_eq!(res, Err((7, CoreError::DoesNotEndWithDQuotes)));
+        }
+
+        #[test]
+        fn empty_string_does_not_panic() {
+            let res = parse::<TestSpec>("");
+            assert_eq!(res, Err((0, CoreError::DoesNotEndWithDQuotes)));
+        }
+
+        #[test]
+        fn pre_validate_is_called() {
+            assert!(parse::<MaxLenSpec>("\"simple\"").is_ok());
+            let res = parse::<MaxLenSpec>("\"simple text\"");
+            assert_eq!(res, Err((0, CoreError::LimitExceeded)));
+        }
+
+        #[test]
+        fn pre_validate_is_not_called_with_the_tail() {
+            let res = parse::<MaxLenSpec>("\"simple\"; and a long tail");
+            assert_eq!(res, Ok(Parsed { quoted_string: "\"simple\"", tail: "; and a long tail" }));
+        }
+
+    }
+
+    mod parse_with_config {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{parse_with_config, ParseConfig, Parsed};
+
+        #[test]
+        fn without_limits() {
+            let res = parse_with_config::<TestSpec>("\"abc\" d", &ParseConfig::default());
+            assert_eq!(res, Ok(Parsed { quoted_string: "\"abc\"", tail: " d" }));
+        }
+
+        #[test]
+        fn max_byte_len() {
+            let config = ParseConfig { max_byte_len: Some(5), ..Default::default() };
+            let res = parse_with_config::<TestSpec>("\"abc\" tail", &config);
+            assert_eq!(res, Ok(Parsed { quoted_string: "\"abc\"", tail: " tail" }));
+            let res = parse_with_config::<TestSpec>("\"abcd\"", &config);
+            assert_eq!(res, Err((5, CoreError::LimitExceeded)));
+        }
+
+        #[test]
+        fn max_content_chars() {
+            let config = ParseConfig { max_content_chars: Some(2), ..Default::default() };
+            let res = parse_with_config::<TestSpec>("\"a\\\"\n+-\n\"", &config);
+            assert_eq!(res, Ok(Parsed { quoted_string: "\"a\\\"\n+-\n\"", tail: "" }));
+            let res = parse_with_config::<TestSpec>("\"abc\"", &config);
+            assert_eq!(res, Err((3, CoreError::LimitExceeded)));
+        }
+
+        #[test]
+        fn parse_errors() {
+            let res = parse_with_config::<TestSpec>("\"abc", &ParseConfig::default());
+            assert_eq!(res, Err((4, CoreError::DoesNotEndWithDQuotes)));
+            let res = parse_with_config::<TestSpec>("abc", &ParseConfig::default());
+            assert_eq!(res, Err((0, CoreError::DoesNotStartWithDQuotes)));
+        }
+    }
+
+    mod parse_complete {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{parse_complete, Parsed};
+
+        #[test]
+        fn whole_input() {
+            let res = parse_complete::<TestSpec>("\"a\\\"b\"");
+            assert_eq!(res, Ok(Parsed { quoted_string: "\"a\\\"b\"", tail: "" }));
+        }
+
+        #[test]
+        fn reject_tail() {
+            let res = parse_complete::<TestSpec>("\"ab\"; c");
+            assert_eq!(res, Err((4, CoreError::QuotedStringAlreadyEnded)));
+        }
+
+        #[test]
+        fn reject_invalid() {
+            let res = parse_complete::<TestSpec>("ab");
+            assert_eq!(res, Err((0, CoreError::DoesNotStartWithDQuotes)));
+        }
+    }
+
+    mod parse_opt {
+        use test_utils::*;
+        use super::super::{parse_opt, Parsed};
+
+        #[test]
+        fn valid() {
+            let res = parse_opt::<TestSpec>("\"ab\"; c");
+            assert_eq!(res, Some(Parsed { quoted_string: "\"ab\"", tail: "; c" }));
+        }
+
+        #[test]
+        fn invalid() {
+            assert_eq!(parse_opt::<TestSpec>("\"ab"), None);
+        }
+    }
+
+    mod parse_with_validator {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{parse_with_validator, Parsed};
+
+        #[test]
+        fn validator_gets_parsed() {
+            let res = parse_with_validator::<TestSpec, _>("\"ab\" c", |parsed| {
+                assert_eq!(parsed, Parsed { quoted_string: "\"ab\"", tail: " c" });
+                true
+            });
+            assert_eq!(res, Ok(Parsed { quoted_string: "\"ab\"", tail: " c" }));
+        }
+
+        #[test]
+        fn parse_error_skips_validator() {
+            let res = parse_with_validator::<TestSpec, _>("\"ab", |_| panic!("called validator"));
+            assert_eq!(res, Err((3, CoreError::DoesNotEndWithDQuotes)));
+        }
+    }
+
+    mod parse_lenient {
+        use std::borrow::Cow;
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{parse_lenient, LenientParsed, LenientFix};
+
+        #[test]
+        fn valid_input_is_borrowed() {
+            let res = parse_lenient::<TestSpec>("\"ab\" c").unwrap();
+            assert_eq!(res, LenientParsed {
+                quoted_string: Cow::Borrowed("\"ab\""),
+                tail: " c",
+                fixes: Vec::new()
+            });
+        }
+
+        #[test]
+        fn missing_closing_quote() {
+            let res = parse_lenient::<TestSpec>("\"a\\\"b").unwrap();
+            assert_eq!(res.quoted_string, "\"a\\\"b\"");
+            assert_eq!(res.tail, "");
+            assert_eq!(res.fixes, &[LenientFix::MissingClosingQuote]);
+        }
+
+        #[test]
+        fn trailing_escape() {
+            let res = parse_lenient::<TestSpec>("\"\\").unwrap();
+            assert_eq!(res.quoted_string, "\"\\\\\"");
+            assert_eq!(res.fixes, &[LenientFix::TrailingEscape, LenientFix::MissingClosingQuote]);
+        }
+
+        #[test]
+        fn unfixable_errors() {
+            assert_eq!(parse_lenient::<TestSpec>(""), Err((0, CoreError::DoesNotEndWithDQuotes)));
+            assert_eq!(parse_lenient::<TestSpec>("ab"), Err((0, CoreError::DoesNotStartWithDQuotes)));
+            assert_eq!(parse_lenient::<TestSpec>("\"a\0b"), Err((2, CoreError::InvalidChar)));
+            // ends in the custom state of the test spec
+            assert_eq!(parse_lenient::<TestSpec>("\"a\n+"), Err((4, CoreError::DoesNotEndWithDQuotes)));
+        }
+    }
+
+    mod validate {
+        use test_utils::*;
+        use super::super::validate;
+
+        #[test]
+        fn accept_valid_quoted_string() {
+            assert!(validate::<TestSpec>("\"that\\\"s strange\""));
+        }
+
+        #[test]
+        fn reject_invalid_quoted_string() {
+            assert!(!validate::<TestSpec>("ups"))
+        }
+
+        #[test]
+        fn reject_quoted_string_shorter_than_input() {
+            assert!(!validate::<TestSpec>("\"nice!\"ups whats here?\""))
+        }
+
+    }
+
+    mod validate_symmetric {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::validate_symmetric;
+
+        #[test]
+        fn exactly_one_quoted_string() {
+            assert_eq!(validate_symmetric::<TestSpec>("\"a\\\"b\""), Ok(true));
+            assert_eq!(validate_symmetric::<TestSpec>("\"\""), Ok(true));
+        }
+
+        #[test]
+        fn with_tail() {
+            assert_eq!(validate_symmetric::<TestSpec>("\"a\" "), Ok(false));
+            assert_eq!(validate_symmetric::<TestSpec>("\"\"\"\""), Ok(false));
+        }
+
+        #[test]
+        fn invalid() {
+            assert_eq!(validate_symmetric::<TestSpec>("\"a"), Err((2, CoreError::DoesNotEndWithDQuotes)));
+            assert_eq!(validate_symmetric::<TestSpec>(" \"a\""), Err((0, CoreError::DoesNotStartWithDQuotes)));
+        }
+    }
+
+    mod validate_all {
+        use test_utils::*;
+        use super::super::{validate_all, validate_any, validate_none};
+
+        #[test]
+        fn empty_input() {
+            let empty: Vec<&str> = Vec::new();
+            assert!(validate_all::<TestSpec, _>(empty.clone()).is_empty());
+            assert!(!validate_any::<TestSpec, _>(empty.clone()));
+            assert!(validate_none::<TestSpec, _>(empty));
+        }
+
+        #[test]
+        fn mixed_input() {
+            let inputs = ["\"a\"", "\"b", "\"c\"d"];
+            assert_eq!(validate_all::<TestSpec, _>(inputs.iter().cloned()), &[true, false, false]);
+            assert!(validate_any::<TestSpec, _>(inputs.iter().cloned()));
+            assert!(!validate_none::<TestSpec, _>(inputs.iter().cloned()));
+        }
+    }
+
+    mod validation_report {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{validation_report, parse};
+
+        #[test]
+        fn valid() {
+            let report = validation_report::<TestSpec>("\"ab\n+-\nc\"");
+            assert!(report.is_valid());
+            assert_eq!(report.content_len, Some(3));
+        }
+
+        #[test]
+        fn stops_on_missing_start_quote() {
+            let report = validation_report::<TestSpec>("a\0");
+            assert_eq!(report.issues, &[(0, CoreError::DoesNotStartWithDQuotes)]);
+        }
+
+        #[test]
+        fn stops_on_tail() {
+            let report = validation_report::<TestSpec>("\"a\0\"b\0");
+            assert_eq!(report.issues, &[
+                (2, CoreError::InvalidChar),
+                (4, CoreError::QuotedStringAlreadyEnded)
+            ]);
+            assert_eq!(report.content_len, None);
+        }
+
+        #[test]
+        fn counts_non_ascii_chars_once() {
+            let report = validation_report::<AnyCharSpec>("\"\u{e4}\u{1F600}b\"");
+            assert!(report.is_valid());
+            assert_eq!(report.content_len, Some(3));
+        }
+
+        #[test]
+        fn reports_invalid_non_ascii_char_once() {
+            let report = validation_report::<TestSpec>("\"a\u{e4}b\0\"");
+            assert_eq!(report.issues, &[(2, CoreError::InvalidChar), (5, CoreError::InvalidChar)]);
+        }
+
+        #[test]
+        fn first_issue_matches_parse() {
+            for input in &["\"a\u{e4}b\"", "\"ab", "ab\"", "\"a\\\u{e4}\"", "\"a\"b"] {
+                let report = validation_report::<TestSpec>(input);
+                let parse_err = parse::<TestSpec>(input).and_then(|parsed| {
+                    if parsed.tail.is_empty() { Ok(()) }
+                    else { Err((parsed.quoted_string.len(), CoreError::QuotedStringAlreadyEnded)) }
+                });
+                assert_eq!(Err(report.issues[0]), parse_err, "input: {:?}", input);
+            }
+        }
+    }
+
+    mod validate_parameter_name {
+        use test_utils::*;
+        use spec::LanguageTagValidator;
+        use super::super::validate_parameter_name;
+
+        #[test]
+        fn valid() {
+            assert!(validate_parameter_name::<TestUnquotedValidator>("abc.de"));
+            assert!(validate_parameter_name::<LanguageTagValidator>("en-US"));
+        }
+
+        #[test]
+        fn invalid_char() {
+            assert!(!validate_parameter_name::<TestUnquotedValidator>("abc de"));
+        }
 
         #[test]
-        fn reject_tailing_escape() {
-            let res = parse::<TestSpec>("\"simple\\\"");
-            assert_eq!(res, Err((9, CoreError::DoesNotEndWithDQuotes)));
+        fn invalid_end() {
+            assert!(!validate_parameter_name::<TestUnquotedValidator>("abc"));
+            assert!(!validate_parameter_name::<LanguageTagValidator>("en-"));
         }
 
         #[test]
-        fn reject_unquoted_quotable() {
-            let res = parse::<TestSpec>("\"simp\\\0le\"");
-            assert_eq!(res, Err((6, CoreError::UnquoteableCharQuoted)));
+        fn empty() {
+            assert!(!validate_parameter_name::<LanguageTagValidator>(""));
         }
+    }
+
+    mod validate_length_range {
+        use test_utils::*;
+        use super::super::validate_length_range;
 
         #[test]
-        fn reject_missing_closing_dquotes() {
-            let res = parse::<TestSpec>("\"simple");
-            assert_eq!(res, Err((7, CoreError::DoesNotEndWithDQuotes)));
+        fn in_range() {
+            assert!(validate_length_range::<TestSpec>("\"ab\"", 2, 2));
+            assert!(validate_length_range::<TestSpec>("\"a\\\"\"", 0, 2));
         }
 
         #[test]
-        fn empty_string_does_not_panic() {
-            let res = parse::<TestSpec>("");
+        fn counts_decoded_chars() {
+            assert!(validate_length_range::<AnyCharSpec>("\"äö\"", 2, 2));
+            assert!(validate_length_range::<TestSpec>("\"a\n\nb\"", 2, 2));
+        }
+
+        #[test]
+        fn out_of_range() {
+            assert!(!validate_length_range::<TestSpec>("\"ab\"", 3, 5));
+            assert!(!validate_length_range::<TestSpec>("\"abc\"", 0, 2));
+        }
+
+        #[test]
+        fn invalid() {
+            assert!(!validate_length_range::<TestSpec>("\"ab\"c", 0, 10));
+            assert!(!validate_length_range::<TestSpec>("\"ab", 0, 10));
+        }
+    }
+
+    mod validate_no_leading_trailing_ws {
+        use test_utils::*;
+        use super::super::validate_no_leading_trailing_ws;
+
+        #[test]
+        fn without_ws() {
+            assert!(validate_no_leading_trailing_ws::<TestSpec>("\"a\""));
+            assert!(validate_no_leading_trailing_ws::<TestSpec>("\"a  b\""));
+            assert!(validate_no_leading_trailing_ws::<TestSpec>("\"\""));
+        }
+
+        #[test]
+        fn with_ws() {
+            assert!(!validate_no_leading_trailing_ws::<TestSpec>("\"  a\""));
+            assert!(!validate_no_leading_trailing_ws::<TestSpec>("\"a \""));
+            assert!(!validate_no_leading_trailing_ws::<TestSpec>("\" \""));
+        }
+
+        #[test]
+        fn ws_in_quoted_pair_counts() {
+            assert!(!validate_no_leading_trailing_ws::<TestSpec>("\"\\ a\""));
+        }
+
+        #[test]
+        fn non_semantic_ws_is_ignored() {
+            assert!(validate_no_leading_trailing_ws::<TestSpec>("\"\n\na\n+-\n\""));
+        }
+
+        #[test]
+        fn invalid() {
+            assert!(!validate_no_leading_trailing_ws::<TestSpec>("\"a\" b"));
+            assert!(!validate_no_leading_trailing_ws::<TestSpec>("\"a"));
+        }
+    }
+
+    mod parse_structured_value {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::parse_structured_value;
+
+        #[test]
+        fn simple() {
+            let parsed = parse_structured_value::<TestSpec>("X-Name: \"a\\\"b\"").unwrap();
+            assert_eq!(parsed.quoted_string, "\"a\\\"b\"");
+            assert_eq!(parsed.tail, "");
+        }
+
+        #[test]
+        fn strips_folding_white_space() {
+            let parsed = parse_structured_value::<TestSpec>("X-Name:\r\n \"ab\" \r\n").unwrap();
+            assert_eq!(parsed.quoted_string, "\"ab\"");
+            assert_eq!(parsed.tail, "");
+        }
+
+        #[test]
+        fn keeps_tail() {
+            let parsed = parse_structured_value::<TestSpec>("X-Name: \"ab\"; c").unwrap();
+            assert_eq!(parsed.quoted_string, "\"ab\"");
+            assert_eq!(parsed.tail, "; c");
+        }
+
+        #[test]
+        fn missing_colon() {
+            let res = parse_structured_value::<TestSpec>("X-Name \"ab\"");
+            assert_eq!(res, Err((11, CoreError::MissingColon)));
+        }
+
+        #[test]
+        fn error_index_is_relative_to_line() {
+            let res = parse_structured_value::<TestSpec>("X-Name: ab");
+            assert_eq!(res, Err((8, CoreError::DoesNotStartWithDQuotes)));
+        }
+
+        #[test]
+        fn skips_comments_around_value() {
+            let parsed = parse_structured_value::<TestSpec>("X-Name: (x) \"v\" (y)").unwrap();
+            assert_eq!(parsed.quoted_string, "\"v\"");
+            assert_eq!(parsed.tail, "");
+        }
+
+        #[test]
+        fn skips_multiple_comments() {
+            let parsed = parse_structured_value::<TestSpec>("X-Name:(a)\r\n (b (c)) \"v\" (d); e")
+                .unwrap();
+            assert_eq!(parsed.quoted_string, "\"v\"");
+            assert_eq!(parsed.tail, "; e");
+        }
+
+        #[test]
+        fn unclosed_comment() {
+            let res = parse_structured_value::<TestSpec>("X-Name: (x \"v\"");
+            assert_eq!(res, Err((14, CoreError::UnclosedComment)));
+        }
+    }
+
+    mod streaming_parser {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::StreamingParser;
+
+        #[test]
+        fn single_chunk() {
+            let mut parser = StreamingParser::<TestSpec>::new();
+            assert_eq!(parser.push_chunk(b"\"simple\"; abc"), Ok(Some(8)));
+            assert!(parser.did_end());
+        }
+
+        #[test]
+        fn multiple_chunks() {
+            let mut parser = StreamingParser::<TestSpec>::new();
+            assert_eq!(parser.push_chunk(b"\"si"), Ok(None));
+            assert_eq!(parser.push_chunk(b""), Ok(None));
+            assert_eq!(parser.push_chunk(b"m\\"), Ok(None));
+            assert_eq!(parser.push_chunk(b"\"ple\""), Ok(Some(10)));
+            assert_eq!(parser.bytes_consumed(), 10);
+        }
+
+        #[test]
+        fn push_after_end() {
+            let mut parser = StreamingParser::<TestSpec>::new();
+            assert_eq!(parser.push_chunk(b"\"a\""), Ok(Some(3)));
+            assert_eq!(parser.push_chunk(b"\"b\""), Ok(Some(3)));
+        }
+
+        #[test]
+        fn error_index_is_absolute() {
+            let mut parser = StreamingParser::<TestSpec>::new();
+            assert_eq!(parser.push_chunk(b"\"ab"), Ok(None));
+            assert_eq!(parser.push_chunk(b"c\\\0"), Err((5, CoreError::UnquoteableCharQuoted)));
+        }
+    }
+
+    mod validate_content_pair_count {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::validate_content_pair_count;
+
+        #[test]
+        fn no_quoted_pairs() {
+            assert_eq!(validate_content_pair_count::<TestSpec>("\"abc def\""), Ok(0));
+        }
+
+        #[test]
+        fn quoted_pairs() {
+            assert_eq!(validate_content_pair_count::<TestSpec>(r#""a\"b\\c\d""#), Ok(3));
+        }
+
+        #[test]
+        fn reject_tail() {
+            assert_eq!(validate_content_pair_count::<TestSpec>("\"a\"b"),
+                Err((3, CoreError::QuotedStringAlreadyEnded)));
+        }
+
+        #[test]
+        fn reject_missing_end() {
+            assert_eq!(validate_content_pair_count::<TestSpec>("\"a\\\""),
+                Err((4, CoreError::DoesNotEndWithDQuotes)));
+        }
+    }
+
+    mod parse_folded_header {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{parse_folded_header, FoldedParsed};
+
+        #[test]
+        fn single_line() {
+            let res = parse_folded_header::<TestSpec>(&["\"a b\" c"]);
+            assert_eq!(res, Ok(FoldedParsed {
+                quoted_string: "\"a b\"".to_owned(),
+                tail: " c".to_owned()
+            }));
+        }
+
+        #[test]
+        fn joins_continuation_lines() {
+            let res = parse_folded_header::<TestSpec>(&["\"a\n", " \tb\r\n", "  c\""]).unwrap();
+            assert_eq!(res.quoted_string, "\"a b c\"");
+            assert_eq!(res.tail, "");
+        }
+
+        #[test]
+        fn error_index_is_relative_to_unfolded_input() {
+            let res = parse_folded_header::<TestSpec>(&["\"a", "  b"]);
+            assert_eq!(res, Err((4, CoreError::DoesNotEndWithDQuotes)));
+        }
+
+        #[test]
+        fn no_lines() {
+            let res = parse_folded_header::<TestSpec>(&[]);
             assert_eq!(res, Err((0, CoreError::DoesNotEndWithDQuotes)));
         }
+    }
+
+    mod parse_header_field {
+        use std::borrow::Cow;
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{parse_header_field, HeaderField};
+
+        #[test]
+        fn simple_value_is_borrowed() {
+            let res = parse_header_field::<TestSpec>("X-Name:  \"a b\"\r\n");
+            assert_eq!(res, Ok(HeaderField { name: "X-Name", value: Cow::Borrowed("\"a b\"") }));
+        }
+
+        #[test]
+        fn unfolds_value() {
+            let field = parse_header_field::<TestSpec>("X-Name: a\r\n b\r\n\tc").unwrap();
+            assert_eq!(field.value, "a b\tc");
+        }
 
+        #[test]
+        fn removes_nested_comments() {
+            let field = parse_header_field::<TestSpec>("X-Name: a(b (c\\)) d)e (f)").unwrap();
+            assert_eq!(field.value, "a e");
+        }
+
+        #[test]
+        fn keeps_comments_in_quoted_strings() {
+            let field = parse_header_field::<TestSpec>("X-Name: \"(a)\" (b)").unwrap();
+            assert_eq!(field.value, "\"(a)\"");
+        }
+
+        #[test]
+        fn errors() {
+            assert_eq!(parse_header_field::<TestSpec>("X-Name"), Err((6, CoreError::MissingColon)));
+            assert_eq!(parse_header_field::<TestSpec>(" : a"), Err((0, CoreError::ZeroSizedValue)));
+            assert_eq!(parse_header_field::<TestSpec>("X: a (b"), Err((7, CoreError::UnclosedComment)));
+            assert_eq!(parse_header_field::<TestSpec>("X: (a\\\0)"), Err((6, CoreError::UnquoteableCharQuoted)));
+            assert_eq!(parse_header_field::<TestSpec>("X: a \"b"), Err((7, CoreError::DoesNotEndWithDQuotes)));
+        }
+
+        #[test]
+        fn rejects_crlf_not_followed_by_white_space() {
+            let res = parse_header_field::<TestSpec>("X-Name: a\r\nX-Other: b");
+            assert_eq!(res, Err((9, CoreError::UnexpectedTrailingInput)));
+        }
+
+        #[test]
+        fn allows_crlf_at_end_of_line() {
+            let field = parse_header_field::<TestSpec>("X-Name: a\r\n").unwrap();
+            assert_eq!(field.value, "a");
+        }
+
+        #[test]
+        fn rejects_invalid_field_names() {
+            assert_eq!(parse_header_field::<TestSpec>("X Name: a"), Err((1, CoreError::InvalidChar)));
+            assert_eq!(parse_header_field::<TestSpec>(" X: a"), Err((0, CoreError::InvalidChar)));
+            assert_eq!(parse_header_field::<TestSpec>("X-N\u{e4}me: a"), Err((3, CoreError::InvalidChar)));
+        }
+
+        #[test]
+        fn allows_white_space_before_colon() {
+            let field = parse_header_field::<TestSpec>("X-Name \t: a").unwrap();
+            assert_eq!(field.name, "X-Name");
+        }
     }
 
-    mod validate {
+    mod iter_quoted_strings {
         use test_utils::*;
-        use super::super::validate;
+        use error::CoreError;
+        use super::super::{iter_quoted_strings, Parsed};
 
         #[test]
-        fn accept_valid_quoted_string() {
-            assert!(validate::<TestSpec>("\"that\\\"s strange\""));
+        fn no_quoted_strings() {
+            let mut iter = iter_quoted_strings::<TestSpec>("abc def");
+            assert_eq!(iter.next(), None);
+            assert_eq!(iter.rest(), "");
         }
 
         #[test]
-        fn reject_invalid_quoted_string() {
-            assert!(!validate::<TestSpec>("ups"))
+        fn multiple_quoted_strings() {
+            let mut iter = iter_quoted_strings::<TestSpec>("\"a\"; \"b\" tail");
+            assert_eq!(iter.next(), Some(Ok(Parsed { quoted_string: "\"a\"", tail: "; \"b\" tail" })));
+            assert_eq!(iter.rest(), "; \"b\" tail");
+            assert_eq!(iter.next(), Some(Ok(Parsed { quoted_string: "\"b\"", tail: " tail" })));
+            assert_eq!(iter.next(), None);
         }
 
         #[test]
-        fn reject_quoted_string_shorter_than_input() {
-            assert!(!validate::<TestSpec>("\"nice!\"ups whats here?\""))
+        fn continues_after_error() {
+            let mut iter = iter_quoted_strings::<TestSpec>("x \"a\\\0\" \"b\"");
+            assert_eq!(iter.next(), Some(Err((5, CoreError::UnquoteableCharQuoted))));
+            assert_eq!(iter.rest(), " \"b\"");
+            assert_eq!(iter.next(), Some(Ok(Parsed { quoted_string: "\"b\"", tail: "" })));
+            assert_eq!(iter.next(), None);
+        }
+
+        #[test]
+        fn skips_quoted_pairs_in_malformed_quoted_string() {
+            let mut iter = iter_quoted_strings::<TestSpec>("\"\0\\\" x\" \"b\"");
+            assert_eq!(iter.next(), Some(Err((1, CoreError::InvalidChar))));
+            assert_eq!(iter.rest(), " \"b\"");
+            assert_eq!(iter.next(), Some(Ok(Parsed { quoted_string: "\"b\"", tail: "" })));
+            assert_eq!(iter.next(), None);
+        }
+
+        #[test]
+        fn unclosed_malformed_quoted_string() {
+            let mut iter = iter_quoted_strings::<TestSpec>("x \"a\0 b");
+            assert_eq!(iter.next(), Some(Err((4, CoreError::InvalidChar))));
+            assert_eq!(iter.rest(), "");
+            assert_eq!(iter.next(), None);
+        }
+
+        #[test]
+        fn advances_if_pre_validate_fails() {
+            let mut iter = iter_quoted_strings::<MaxLenSpec>("\"abc\" \"abcdefghijkl\" x");
+            assert_eq!(iter.next(), Some(Ok(Parsed {
+                quoted_string: "\"abc\"",
+                tail: " \"abcdefghijkl\" x"
+            })));
+            assert_eq!(iter.next(), Some(Err((6, CoreError::LimitExceeded))));
+            assert_eq!(iter.rest(), " x");
+            assert_eq!(iter.next(), None);
+        }
+    }
+
+    mod parse_comment {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{parse_comment, ParsedComment};
+
+        #[test]
+        fn simple() {
+            let res = parse_comment::<TestSpec>("(a b)c");
+            assert_eq!(res, Ok(ParsedComment { comment: "(a b)", tail: "c" }));
+        }
+
+        #[test]
+        fn nested_and_quoted_pairs() {
+            let res = parse_comment::<TestSpec>("(a(b\\(c)\\))");
+            assert_eq!(res, Ok(ParsedComment { comment: "(a(b\\(c)\\))", tail: "" }));
+        }
+
+        #[test]
+        fn does_not_start_with_parenthesis() {
+            let res = parse_comment::<TestSpec>(" (a)");
+            assert_eq!(res, Err((0, CoreError::DoesNotStartWithParenthesis)));
+        }
+
+        #[test]
+        fn unclosed() {
+            let res = parse_comment::<TestSpec>("(a(b)");
+            assert_eq!(res, Err((5, CoreError::UnclosedComment)));
+            let res = parse_comment::<TestSpec>("(a\\)");
+            assert_eq!(res, Err((4, CoreError::UnclosedComment)));
+        }
+
+        #[test]
+        fn invalid_quoted_pair() {
+            let res = parse_comment::<TestSpec>("(a\\\0)");
+            assert_eq!(res, Err((3, CoreError::UnquoteableCharQuoted)));
+        }
+    }
+
+    mod parse_as_content_type {
+        use std::borrow::Cow;
+        use test_utils::*;
+        use error::CoreError;
+        use spec::AsciiWordValidator;
+        use super::super::parse_as_content_type;
+
+        #[test]
+        fn media_type_only() {
+            let (media_type, params) =
+                parse_as_content_type::<TestSpec, AsciiWordValidator>(" text/plain ").unwrap();
+            assert_eq!(media_type, Cow::Borrowed("text/plain"));
+            assert!(params.is_empty());
         }
 
+        #[test]
+        fn token_and_quoted_values() {
+            let (media_type, params) = parse_as_content_type::<TestSpec, AsciiWordValidator>(
+                "multipart/mixed; boundary=\"a;\\\"b\" ;charset = utf8;"
+            ).unwrap();
+            assert_eq!(media_type, "multipart/mixed");
+            assert_eq!(params, vec![
+                (Cow::Borrowed("boundary"), Cow::Owned("a;\"b".to_owned())),
+                (Cow::Borrowed("charset"), Cow::Borrowed("utf8"))
+            ]);
+        }
+
+        #[test]
+        fn quoted_value_without_quoted_pairs_is_borrowed() {
+            let (_, params) =
+                parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain;a=\"b c\"").unwrap();
+            assert_eq!(params, vec![(Cow::Borrowed("a"), Cow::Borrowed("b c"))]);
+        }
+
+        #[test]
+        fn empty_media_type() {
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>(" ; a=b");
+            assert_eq!(res, Err((1, CoreError::ZeroSizedValue)));
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("text/; a=b");
+            assert_eq!(res, Err((5, CoreError::ZeroSizedValue)));
+        }
+
+        #[test]
+        fn invalid_media_type() {
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("foo bar; a=b");
+            assert_eq!(res, Err((3, CoreError::InvalidChar)));
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain/x");
+            assert_eq!(res, Err((10, CoreError::UnexpectedTrailingInput)));
+        }
+
+        #[test]
+        fn missing_equals_sign() {
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain; a; b=c");
+            assert_eq!(res, Err((13, CoreError::MissingEqualsSign)));
+        }
+
+        #[test]
+        fn empty_name_or_value() {
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain; =c");
+            assert_eq!(res, Err((12, CoreError::ZeroSizedValue)));
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain; a= ;");
+            assert_eq!(res, Err((15, CoreError::ZeroSizedValue)));
+        }
+
+        #[test]
+        fn text_after_quoted_value() {
+            let res =
+                parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain; a=\"b\" c");
+            assert_eq!(res, Err((18, CoreError::UnexpectedTrailingInput)));
+        }
+
+        #[test]
+        fn text_after_token_value() {
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain; a=b c");
+            assert_eq!(res, Err((16, CoreError::UnexpectedTrailingInput)));
+        }
+
+        #[test]
+        fn invalid_name() {
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain; a b=c");
+            assert_eq!(res, Err((14, CoreError::MissingEqualsSign)));
+        }
+
+        #[test]
+        fn invalid_quoted_value() {
+            let res = parse_as_content_type::<TestSpec, AsciiWordValidator>("text/plain; a=\"b");
+            assert_eq!(res.unwrap_err().1, CoreError::DoesNotEndWithDQuotes);
+        }
+    }
+
+    mod parse_header_param {
+        use std::borrow::Cow;
+        use test_utils::*;
+        use error::CoreError;
+        use spec::{AsciiWordValidator, LanguageTagValidator};
+        use super::super::parse_header_param;
+
+        #[test]
+        fn token_value() {
+            let res = parse_header_param::<TestSpec, AsciiWordValidator>(" ;charset = utf8 ; a=b");
+            assert_eq!(res, Ok((("charset", Cow::Borrowed("utf8")), " ; a=b")));
+        }
+
+        #[test]
+        fn quoted_value() {
+            let res = parse_header_param::<TestSpec, AsciiWordValidator>("a=\"b\\\"c\"d");
+            assert_eq!(res, Ok((("a", Cow::Owned("b\"c".to_owned())), "d")));
+        }
+
+        #[test]
+        fn empty_name_or_value() {
+            let res = parse_header_param::<TestSpec, AsciiWordValidator>("; =b");
+            assert_eq!(res, Err((2, CoreError::ZeroSizedValue)));
+            let res = parse_header_param::<TestSpec, AsciiWordValidator>("a= ;");
+            assert_eq!(res, Err((3, CoreError::ZeroSizedValue)));
+        }
+
+        #[test]
+        fn incomplete_token() {
+            let res = parse_header_param::<TestSpec, LanguageTagValidator>("lang=en-");
+            assert_eq!(res, Err((8, CoreError::InvalidChar)));
+        }
+
+        #[test]
+        fn missing_equals_sign() {
+            let res = parse_header_param::<TestSpec, AsciiWordValidator>("a b");
+            assert_eq!(res, Err((2, CoreError::MissingEqualsSign)));
+        }
+
+        #[test]
+        fn invalid_quoted_value() {
+            let res = parse_header_param::<TestSpec, AsciiWordValidator>("a=\"b");
+            assert_eq!(res, Err((4, CoreError::DoesNotEndWithDQuotes)));
+        }
     }
 
+    mod parse_result_iter {
+        use test_utils::*;
+        use super::super::parse_result_iter;
+
+        #[test]
+        fn yields_raw_slices() {
+            let found = parse_result_iter::<TestSpec>("\"a\\\"b\" x \"c\n+-\n\"").collect::<Vec<_>>();
+            assert_eq!(found, &["\"a\\\"b\"", "\"c\n+-\n\""]);
+        }
+
+        #[test]
+        fn skips_errors() {
+            let found = parse_result_iter::<TestSpec>("\"a\" x \"\0\"").collect::<Vec<_>>();
+            assert_eq!(found, &["\"a\""]);
+        }
 
+        #[test]
+        fn no_quoted_strings() {
+            assert_eq!(parse_result_iter::<TestSpec>("abc").next(), None);
+        }
 
-}
\ No newline at end of file
+        #[test]
+        fn skips_quoted_strings_rejected_by_pre_validate() {
+            let found = parse_result_iter::<MaxLenSpec>("\"abc\" x \"abcdefghijkl\"")
+                .collect::<Vec<_>>();
+            assert_eq!(found, &["\"abc\""]);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+
+use spec::{GeneralQSSpec, ScanAutomaton, PartialCodePoint};
+use error::CoreError;
+use parse::{parse_complete, Parsed};
+use iter::ContentChars;
+
+/// A `&str` known to be exactly one valid quoted string (wrt. `Spec`)
+///
+/// It can be created through `TryFrom<&str>`, which validates the input
+/// once, or through `from_str_unchecked` if the input was already
+/// validated. Afterwards the quoted string can be parsed, unquoted etc.
+/// without having to handle errors which can not happen.
+///
+/// # Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::PrevalidatedStr;
+///
+/// let qs = PrevalidatedStr::<TestSpec>::try_from("\"a\\\"b\"").unwrap();
+/// assert_eq!(qs.parse().tail, "");
+/// assert_eq!(qs.to_content(), "a\"b");
+/// assert_eq!(qs.content_chars(), "a\"b");
+/// assert!(PrevalidatedStr::<TestSpec>::try_from("\"a\" b").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrevalidatedStr<'a, Spec: GeneralQSSpec> {
+    inner: &'a str,
+    _spec: PhantomData<Spec>
+}
+
+impl<'a, Spec> PrevalidatedStr<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    /// wraps the input without validating it
+    ///
+    /// The input is _assumed_ to be exactly one valid quoted string. If
+    /// it isn't the other methods might panic or return wrong results.
+    pub fn from_str_unchecked(quoted_string: &'a str) -> Self {
+        PrevalidatedStr { inner: quoted_string, _spec: PhantomData }
+    }
+
+    /// returns the wrapped quoted string
+    pub fn as_str(&self) -> &'a str {
+        self.inner
+    }
+
+    /// returns the `Parsed` result of parsing the quoted string
+    ///
+    /// As the quoted string spans the whole input, the `tail` is empty.
+    pub fn parse(&self) -> Parsed<'a> {
+        Parsed { quoted_string: self.inner, tail: "" }
+    }
+
+    /// returns the content of the quoted string
+    ///
+    /// In difference to `to_content` the quoted string is not validated
+    /// again, e.g. `Spec::pre_validate` is not called and there are no
+    /// errors to handle. The chars are only fed into the automaton to find
+    /// the quoted-pairs and non-semantic parts which have to be removed. If
+    /// there are none the content is returned borrowed.
+    pub fn to_content(&self) -> Cow<'a, str> {
+        let content = &self.inner[1..self.inner.len() - 1];
+        let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+        // the input is known to be valid, so advancing can not fail
+        let _ = automaton.advance(PartialCodePoint::from_utf8_byte(b'"'));
+        let mut owned: Option<String> = None;
+        for (idx, ch) in content.char_indices() {
+            let emit = automaton.advance(PartialCodePoint::from_code_point(ch as u32)) == Ok(true);
+            match owned {
+                Some(ref mut out) if emit => out.push(ch),
+                None if !emit => owned = Some(content[..idx].to_owned()),
+                _ => {}
+            }
+        }
+        match owned {
+            Some(out) => Cow::Owned(out),
+            None => Cow::Borrowed(content)
+        }
+    }
+
+    /// returns a iterator over the chars of the content of the quoted string
+    pub fn content_chars(&self) -> ContentChars<'a, Spec> {
+        ContentChars::from_str(self.inner)
+    }
+
+    /// compares the content of the quoted string with `other`
+    ///
+    /// In difference to `==`, which compares the raw quoted string, this
+    /// compares the decoded content, e.g. `"a\\b"` is content equal to `ab`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::convert::TryFrom;
+    /// // use your own Spec
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::PrevalidatedStr;
+    ///
+    /// let qs = PrevalidatedStr::<TestSpec>::try_from("\"a\\b\"").unwrap();
+    /// assert!(qs.content_eq("ab"));
+    /// assert!(qs != "ab");
+    /// assert!(qs == "\"a\\b\"");
+    /// ```
+    pub fn content_eq(&self, other: &str) -> bool {
+        self.content_chars() == other
+    }
+}
+
+/// compares the raw quoted string (use `content_eq` to compare the content)
+impl<'a, Spec> PartialEq<str> for PrevalidatedStr<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    fn eq(&self, other: &str) -> bool {
+        self.inner == other
+    }
+}
+
+/// compares the raw quoted string (use `content_eq` to compare the content)
+impl<'a, 'b, Spec> PartialEq<&'b str> for PrevalidatedStr<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    fn eq(&self, other: &&'b str) -> bool {
+        self.inner == *other
+    }
+}
+
+/// compares the raw quoted string (use `content_eq` to compare the content)
+impl<'a, Spec> PartialEq<PrevalidatedStr<'a, Spec>> for str
+    where Spec: GeneralQSSpec
+{
+    fn eq(&self, other: &PrevalidatedStr<'a, Spec>) -> bool {
+        self == other.inner
+    }
+}
+
+/// compares the raw quoted string (use `content_eq` to compare the content)
+impl<'a, Spec> PartialEq<PrevalidatedStr<'a, Spec>> for &str
+    where Spec: GeneralQSSpec
+{
+    fn eq(&self, other: &PrevalidatedStr<'a, Spec>) -> bool {
+        *self == other.inner
+    }
+}
+
+impl<'a, Spec> TryFrom<&'a str> for PrevalidatedStr<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    type Error = CoreError;
+
+    fn try_from(quoted_string: &'a str) -> Result<Self, CoreError> {
+        parse_complete::<Spec>(quoted_string).map_err(|(_idx, err)| err)?;
+        Ok(PrevalidatedStr::from_str_unchecked(quoted_string))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+    use std::convert::TryFrom;
+    use test_utils::*;
+    use error::CoreError;
+    use parse::Parsed;
+    use super::PrevalidatedStr;
+
+    #[test]
+    fn try_from_valid() {
+        let qs = PrevalidatedStr::<TestSpec>::try_from("\"simple\"").unwrap();
+        assert_eq!(qs.as_str(), "\"simple\"");
+        assert_eq!(qs.parse(), Parsed { quoted_string: "\"simple\"", tail: "" });
+        assert_eq!(qs.to_content(), Cow::Borrowed("simple"));
+    }
+
+    #[test]
+    fn try_from_invalid() {
+        let res = PrevalidatedStr::<TestSpec>::try_from("\"simple");
+        assert_eq!(res.unwrap_err(), CoreError::DoesNotEndWithDQuotes);
+        let res = PrevalidatedStr::<TestSpec>::try_from("\"simple\"; tail");
+        assert_eq!(res.unwrap_err(), CoreError::QuotedStringAlreadyEnded);
+    }
+
+    #[test]
+    fn to_content_removes_quoted_pairs_and_non_semantic_parts() {
+        let qs = PrevalidatedStr::<TestSpec>::try_from("\"a\\\"b\n+-\nc\"").unwrap();
+        assert_eq!(qs.to_content(), Cow::<str>::Owned("a\"bc".to_owned()));
+    }
+
+    #[test]
+    fn unchecked_to_content_does_not_validate_again() {
+        let qs = PrevalidatedStr::<MaxLenSpec>::from_str_unchecked("\"abcdefghijk\"");
+        assert_eq!(qs.to_content(), Cow::Borrowed("abcdefghijk"));
+    }
+
+    #[test]
+    fn eq_compares_raw_quoted_string() {
+        let qs = PrevalidatedStr::<TestSpec>::from_str_unchecked("\"\\a\"");
+        assert!(qs == "\"\\a\"");
+        assert!("\"\\a\"" == qs);
+        assert!(*"\"\\a\"" == qs);
+        assert!(qs != "\"a\"");
+        assert!("a" != qs);
+    }
+
+    #[test]
+    fn content_eq() {
+        let qs = PrevalidatedStr::<TestSpec>::from_str_unchecked("\"\\a\"");
+        assert!(qs.content_eq("a"));
+        assert!(!qs.content_eq("\\a"));
+    }
+}
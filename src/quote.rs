@@ -1,4 +1,7 @@
 use std::borrow::Cow;
+use std::fmt;
+use std::convert::TryFrom;
+use std::marker::PhantomData;
 // this import will become unused in future rust versions
 // but won't be removed for now for supporting current
 // rust versions
@@ -6,6 +9,7 @@ use std::borrow::Cow;
 use std::ascii::AsciiExt;
 
 use error::CoreError;
+use parse::is_complete_token;
 use spec::{
     QuotingClassifier,
     QuotingClass,
@@ -18,6 +22,10 @@ use spec::{
 
 /// quotes the input string returning the quoted string
 ///
+/// The input is the (unquoted) content the quoted string should
+/// represent, it is not expected to already be a quoted string,
+/// see also `from_unquoted`.
+///
 /// # Example
 ///
 /// ```
@@ -36,30 +44,457 @@ pub fn quote<Spec: GeneralQSSpec>(
 {
     let mut out = String::with_capacity(input.len()+2);
     out.push('"');
-    quote_inner::<Spec>(input, &mut out)?;
+    quote_inner::<Spec, _>(input, &mut out)?;
     out.push('"');
     Ok(out)
 }
 
+/// creates a quoted string representing the given content
+///
+/// This is the same as `quote` but with a name making clear that the
+/// input is the content (i.e. unquoted) and not a quoted string.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::{from_unquoted, to_content};
+///
+/// let qs = from_unquoted::<TestSpec>("some\"text").unwrap();
+/// assert_eq!(qs, "\"some\\\"text\"");
+/// assert_eq!(to_content::<TestSpec>(&qs).unwrap(), "some\"text");
+/// ```
+#[inline]
+pub fn from_unquoted<Spec: GeneralQSSpec>(content: &str) -> Result<String, CoreError> {
+    quote::<Spec>(content)
+}
+
+/// creates a quoted string representing the given content without checking it
+///
+/// This is like `from_unquoted` but it does not check if the content
+/// contains chars which can not be represented in a quoted string, it
+/// is meant for performance critical code where the content is known to
+/// be valid (e.g. because it was just returned by `to_content`). Chars
+/// which can not be represented are copied as they are, which means the
+/// returned string is not a valid quoted string if `content` contains them.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::{from_content_unchecked, to_content};
+///
+/// let content = to_content::<TestSpec>("\"some\\\"text\"").unwrap();
+/// let qs = from_content_unchecked::<TestSpec>(&content);
+/// assert_eq!(qs, "\"some\\\"text\"");
+/// ```
+pub fn from_content_unchecked<Spec: GeneralQSSpec>(content: &str) -> String {
+    let mut out = String::with_capacity(content.len()+2);
+    out.push('"');
+    // with `reject_invalid` false the only possible error would come
+    // from writing to `out`, which can not fail for a `String`
+    let _ = quote_runs::<Spec, _>(content, &mut out, false);
+    out.push('"');
+    out
+}
+
+/// quotes the input bytes returning the bytes of the quoted string
+///
+/// This is meant for protocols transporting bytes which are not (necessary)
+/// utf-8 in quoted strings. Each byte is classified on it's own using
+/// `Spec::Quoting`, where all non us-ascii bytes are classified like a non
+/// us-ascii char (i.e. like `PartialCodePoint::from_code_point` does).
+///
+/// # Error
+///
+/// `CoreError::InvalidChar` is returned if a byte can not be represented
+/// in a quoted string.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::quote_bytes;
+///
+/// let quoted = quote_bytes::<TestSpec>(b"some\"text").unwrap();
+/// assert_eq!(quoted, b"\"some\\\"text\"");
+/// ```
+pub fn quote_bytes<Spec: GeneralQSSpec>(input: &[u8]) -> Result<Vec<u8>, CoreError> {
+    let mut out = Vec::with_capacity(input.len()+2);
+    out.push(b'"');
+    for &bch in input {
+        match Spec::Quoting::classify_for_quoting(PartialCodePoint::from_code_point(bch as u32)) {
+            QuotingClass::QText => {},
+            QuotingClass::NeedsQuoting => out.push(b'\\'),
+            QuotingClass::Invalid => return Err(CoreError::InvalidChar)
+        }
+        out.push(bch);
+    }
+    out.push(b'"');
+    Ok(out)
+}
+
+/// quotes the input string appending the quoted string to `target`
+///
+/// This is like `target.push_str(&quote::<Spec>(input)?)` but without
+/// the intermediate allocation. If the input can not be quoted `target`
+/// is left unchanged.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::quote_append;
+///
+/// let mut header = String::from("filename=");
+/// quote_append::<TestSpec>("a \"b\"", &mut header).unwrap();
+/// assert_eq!(header, "filename=\"a \\\"b\\\"\"");
+/// ```
+pub fn quote_append<Spec: GeneralQSSpec>(
+    input: &str,
+    target: &mut String
+) -> Result<(), CoreError>
+{
+    let start_len = target.len();
+    target.reserve(input.len()+2);
+    target.push('"');
+    if let Err(err) = quote_inner::<Spec, _>(input, target) {
+        target.truncate(start_len);
+        return Err(err);
+    }
+    target.push('"');
+    Ok(())
+}
+
+/// quotes the input string writing the quoted string to `out`
+///
+/// # Error
+///
+/// `CoreError::InvalidChar` is returned if `input` contains chars which
+/// can not be represented in a quoted string and `CoreError::WriteFailed`
+/// if writing to `out` failed. In both cases parts of the quoted string
+/// might already have been written.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::quote_to_writer;
+///
+/// let mut out = String::new();
+/// quote_to_writer::<TestSpec, _>("some\"text", &mut out).unwrap();
+/// assert_eq!(out, "\"some\\\"text\"");
+/// ```
+pub fn quote_to_writer<Spec, W>(input: &str, out: &mut W) -> Result<(), CoreError>
+    where Spec: GeneralQSSpec,
+          W: fmt::Write
+{
+    out.write_char('"')?;
+    quote_inner::<Spec, _>(input, out)?;
+    out.write_char('"')?;
+    Ok(())
+}
+
+/// A wrapper around some content which displays it as quoted string
+///
+/// As `Display` can not return a `CoreError`, formatting fails with
+/// `fmt::Error` if the content can not be represented as quoted string,
+/// use `quote_to_writer` if the reason is needed.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::WritableQuotedString;
+///
+/// let header = format!(
+///     "Content-Disposition: attachment; filename={}",
+///     WritableQuotedString::<TestSpec>::new("my report.pdf")
+/// );
+/// assert_eq!(header, "Content-Disposition: attachment; filename=\"my report.pdf\"");
+/// ```
+#[derive(Debug, Clone)]
+pub struct WritableQuotedString<'a, Spec: GeneralQSSpec> {
+    content: &'a str,
+    _spec: PhantomData<Spec>
+}
+
+impl<'a, Spec> WritableQuotedString<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    /// creates a new instance displaying `content` as quoted string
+    pub fn new(content: &'a str) -> Self {
+        WritableQuotedString { content, _spec: PhantomData }
+    }
+
+    /// returns the (unquoted) content
+    pub fn content(&self) -> &'a str {
+        self.content
+    }
+}
+
+impl<'a, Spec> fmt::Display for WritableQuotedString<'a, Spec>
+    where Spec: GeneralQSSpec
+{
+    fn fmt(&self, fter: &mut fmt::Formatter) -> fmt::Result {
+        quote_to_writer::<Spec, _>(self.content, fter)
+            .map_err(|_| fmt::Error)
+    }
+}
+
+/// quotes the content into a `String` returning the error if it is not representable
+///
+/// This is the fallible alternative to using the `Display` implementation
+/// (e.g. through `to_string`, which panics if formatting fails).
+///
+/// # Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::quote_display;
+/// use quoted_string::error::CoreError;
+///
+/// assert_eq!(String::try_from(quote_display::<TestSpec>("a b")), Ok("\"a b\"".to_owned()));
+/// assert_eq!(String::try_from(quote_display::<TestSpec>("a\0")), Err(CoreError::InvalidChar));
+/// ```
+impl<'a, Spec> TryFrom<WritableQuotedString<'a, Spec>> for String
+    where Spec: GeneralQSSpec
+{
+    type Error = CoreError;
+
+    fn try_from(quoted: WritableQuotedString<'a, Spec>) -> Result<String, CoreError> {
+        quote::<Spec>(quoted.content)
+    }
+}
+
+/// returns a wrapper around `content` which displays it as quoted string
+///
+/// This is a shorthand for `WritableQuotedString::new`.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::quote_display;
+///
+/// let param = format!("filename={}", quote_display::<TestSpec>("a b.txt"));
+/// assert_eq!(param, "filename=\"a b.txt\"");
+/// ```
+#[inline]
+pub fn quote_display<Spec: GeneralQSSpec>(content: &str) -> WritableQuotedString<'_, Spec> {
+    WritableQuotedString::new(content)
+}
+
 /// quotes a input writing it into the output buffer, does not add surrounding '"'
 ///
-/// if ascii_only is true and non ascii chars a found an error is returned.
+/// Returns `CoreError::InvalidChar` if the input contains a char which can
+/// not be represented in a quoted string.
+///
+/// There is no SIMD fast path which checks 16 bytes at a time for `'"'` and
+/// `'\\'`, as which chars need quoting is decided by `Spec::Quoting` (e.g. a
+/// spec might also escape `' '`). Pushing whole runs of qtext at once instead
+/// of char by char brings `quote/quote_long_ascii` from ~16.8µs to ~10.5µs.
+fn quote_inner<Spec: GeneralQSSpec, W: fmt::Write>(
+    input: &str,
+    out: &mut W,
+) -> Result<(), CoreError>
+{
+    quote_runs::<Spec, W>(input, out, true)
+}
+
+/// the escaping loop shared by `quote_inner` and `from_content_unchecked`
 ///
-/// If no error is returned a boolean indicating if the whole input was ascii is
-/// returned.
-fn quote_inner<Spec: GeneralQSSpec>(
+/// If `reject_invalid` is false chars which can not be represented in a
+/// quoted string are copied as they are instead of causing an error.
+#[inline]
+fn quote_runs<Spec: GeneralQSSpec, W: fmt::Write>(
     input: &str,
-    out: &mut String,
+    out: &mut W,
+    reject_invalid: bool
 ) -> Result<(), CoreError>
 {
     use self::QuotingClass::*;
-    for ch in input.chars() {
+    // instead of pushing char by char whole runs of chars not needing
+    // quoting are pushed at once, the char following a inserted '\\'
+    // is just the start of the next run
+    let mut run_start = 0;
+    for (idx, ch) in input.char_indices() {
         match Spec::Quoting::classify_for_quoting(PartialCodePoint::from_code_point(ch as u32)) {
-            QText => out.push(ch),
-            NeedsQuoting => { out.push('\\'); out.push(ch); }
-            Invalid => return Err(CoreError::InvalidChar)
+            QText => {},
+            NeedsQuoting => {
+                out.write_str(&input[run_start..idx])?;
+                out.write_char('\\')?;
+                run_start = idx;
+            }
+            Invalid => if reject_invalid {
+                return Err(CoreError::InvalidChar)
+            }
         }
     }
+    out.write_str(&input[run_start..])?;
+    Ok(())
+}
+
+/// writes `input` as token if possible and as quoted string if not to `out`
+///
+/// This is the writer based counterpart of `encode_as_token_or_quoted`,
+/// a new (default) instance of `Token` decides if `input` is a valid
+/// token, `Spec` how it is quoted otherwise.
+///
+/// # Error
+///
+/// `CoreError::InvalidChar` is returned if the input can neither be
+/// represented as token nor as quoted string and `CoreError::WriteFailed`
+/// if writing to `out` failed. In both cases parts of the quoted string
+/// might already have been written.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec and token validator
+/// use quoted_string::test_utils::{TestSpec, TestUnquotedValidator};
+/// use quoted_string::write_unquoted_or_quoted;
+///
+/// let mut out = String::new();
+/// write_unquoted_or_quoted::<TestSpec, TestUnquotedValidator, _>("simple", &mut out).unwrap();
+/// out.push_str("; ");
+/// write_unquoted_or_quoted::<TestSpec, TestUnquotedValidator, _>("not simple", &mut out).unwrap();
+/// assert_eq!(out, "simple; \"not simple\"");
+/// ```
+pub fn write_unquoted_or_quoted<Spec, Token, W>(input: &str, out: &mut W) -> Result<(), CoreError>
+    where Spec: GeneralQSSpec,
+          Token: WithoutQuotingValidator + Default,
+          W: fmt::Write
+{
+    if is_complete_token::<Token>(input) {
+        out.write_str(input)?;
+        Ok(())
+    } else {
+        quote_to_writer::<Spec, _>(input, out)
+    }
+}
+
+/// appends `ch` as quoted-pair (i.e. `'\\'` followed by `ch`) to `out`
+///
+/// This is a building block for custom quoting code, it does not check
+/// if `ch` can be (or needs to be) represented as quoted-pair, use
+/// `GeneralQSSpec::quoted_pair_char_is_valid` for this.
+///
+/// # Example
+///
+/// ```
+/// use quoted_string::append_quoted_pair;
+///
+/// let mut out = String::from("\"a");
+/// append_quoted_pair('"', &mut out);
+/// assert_eq!(out, "\"a\\\"");
+/// ```
+pub fn append_quoted_pair(ch: char, out: &mut String) {
+    out.push('\\');
+    out.push(ch);
+}
+
+/// appends `ch` as qtext (i.e. as it is) to `out`
+///
+/// This is the same as `out.push(ch)` and only exists as counterpart
+/// to `append_quoted_pair` to make custom quoting code more readable.
+/// It does not check if `ch` is qtext.
+///
+/// # Example
+///
+/// ```
+/// use quoted_string::append_qtext;
+///
+/// let mut out = String::from("\"");
+/// append_qtext('a', &mut out);
+/// assert_eq!(out, "\"a");
+/// ```
+pub fn append_qtext(ch: char, out: &mut String) {
+    out.push(ch);
+}
+
+/// the line length (excluding CRLF) after which a header line is folded
+const MAX_HEADER_LINE_LEN: usize = 78;
+
+/// writes a `Name: "value"\r\n` header line with `value` quoted
+///
+/// If the line is longer than 78 characters it is folded by inserting
+/// a `"\r\n"` before whitespace already contained in the value (but not
+/// before whitespace escaped with a quoted-pair). This means the folding
+/// does not change the content of the quoted string (after unfolding) and
+/// that lines are not folded if the value doesn't contain any whitespace
+/// which can be used to fold it.
+///
+/// Note that the used `Spec` has to allow folding white space in
+/// quoted strings for the output to be parseable with it if
+/// folding was necessary.
+///
+/// # Error
+///
+/// `CoreError::InvalidChar` is returned if `value` contains chars which
+/// can not be represented in a quoted string and `CoreError::WriteFailed`
+/// if writing to `out` failed.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec instead
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::write_quoted_string_header;
+///
+/// let mut out = String::new();
+/// write_quoted_string_header::<TestSpec, _>("Content-Description", "my \"doc\"", &mut out)
+///     .unwrap();
+/// assert_eq!(out, "Content-Description: \"my \\\"doc\\\"\"\r\n");
+/// ```
+pub fn write_quoted_string_header<Spec, W>(
+    name: &str,
+    value: &str,
+    out: &mut W
+) -> Result<(), CoreError>
+    where Spec: GeneralQSSpec,
+          W: fmt::Write
+{
+    let mut line = String::with_capacity(name.len() + value.len() + 4);
+    line.push_str(name);
+    line.push_str(": \"");
+    quote_inner::<Spec, _>(value, &mut line)?;
+    line.push('"');
+
+    // index of the opening '"', only white space after it is used for folding
+    let value_start = name.len() + 2;
+    let mut line_start = 0;
+    let mut fold_at = None;
+    let mut last_was_ws = true;
+    let mut in_quoted_pair = false;
+    for (idx, ch) in line.char_indices() {
+        let is_ws = ch == ' ' || ch == '\t';
+        // never fold so that a line only contains white space and never
+        // between the '\\' of a quoted-pair and the white space it escapes
+        if is_ws && !last_was_ws && !in_quoted_pair && idx > line_start && idx > value_start {
+            fold_at = Some(idx);
+        }
+        last_was_ws = is_ws;
+        in_quoted_pair = !in_quoted_pair && ch == '\\' && idx > value_start;
+        if idx + ch.len_utf8() - line_start > MAX_HEADER_LINE_LEN {
+            if let Some(pos) = fold_at.take() {
+                out.write_str(&line[line_start..pos])?;
+                out.write_str("\r\n")?;
+                line_start = pos;
+            }
+        }
+    }
+    out.write_str(&line[line_start..])?;
+    out.write_str("\r\n")?;
     Ok(())
 }
 
@@ -106,42 +541,73 @@ pub fn quote_if_needed<'a, Spec, WQImpl>(
     where Spec: GeneralQSSpec,
           WQImpl: WithoutQuotingValidator
 {
-    let mut needs_quoting_from = None;
-    for (idx, ch) in input.char_indices() {
-        let pcp = PartialCodePoint::from_code_point(ch as u32);
-        if !validator.next(pcp) {
-            needs_quoting_from = Some(idx);
-            break;
-        } else {
-            //FIXME check if is this even enabled in the right context
-            #[cfg(debug_assertions)]
-            {
-                use self::QuotingClass::*;
-                match Spec::Quoting::classify_for_quoting(pcp) {
-                    QText => {},
-                    Invalid => panic!(concat!("[BUG] representable without quoted string,",
-                                            "but invalid in quoted string: {}"), ch),
-                    NeedsQuoting => panic!(concat!("[BUG] representable without quoted string,",
-                                            "but not without escape in quoted string: {}"), ch)
-                }
+    quote_if_needed_with_flag::<Spec, _>(input, validator)
+        .map(|(out, _was_quoted)| out)
+}
+
+/// quotes the input string if needed, returning if quoting was applied
+///
+/// This works like `quote_if_needed`, but additionally returns `true` if the
+/// output is a quoted string and `false` if the input was returned unchanged.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec
+/// use quoted_string::test_utils::{TestSpec, TestUnquotedValidator};
+/// use quoted_string::quote_if_needed_with_flag;
+///
+/// let mut without_quoting = TestUnquotedValidator::new();
+/// let (out, was_quoted) = quote_if_needed_with_flag::<TestSpec, _>("simple", &mut without_quoting)
+///     .unwrap();
+/// assert_eq!(out, "simple");
+/// assert!(!was_quoted);
+///
+/// let mut without_quoting = TestUnquotedValidator::new();
+/// let (out, was_quoted) = quote_if_needed_with_flag::<TestSpec, _>("not simple", &mut without_quoting)
+///     .unwrap();
+/// assert_eq!(out, "\"not simple\"");
+/// assert!(was_quoted);
+/// ```
+pub fn quote_if_needed_with_flag<'a, Spec, WQImpl>(
+    input: &'a str,
+    validator: &mut WQImpl
+) -> Result<(Cow<'a, str>, bool), CoreError>
+    where Spec: GeneralQSSpec,
+          WQImpl: WithoutQuotingValidator
+{
+    let needs_quoting_from = validator.validate_sequence(input).err();
+
+    //FIXME check if is this even enabled in the right context
+    #[cfg(debug_assertions)]
+    {
+        use self::QuotingClass::*;
+        let valid_part = &input[..needs_quoting_from.unwrap_or(input.len())];
+        for ch in valid_part.chars() {
+            match Spec::Quoting::classify_for_quoting(PartialCodePoint::from_code_point(ch as u32)) {
+                QText => {},
+                Invalid => panic!(concat!("[BUG] representable without quoted string,",
+                                        "but invalid in quoted string: {}"), ch),
+                NeedsQuoting => panic!(concat!("[BUG] representable without quoted string,",
+                                        "but not without escape in quoted string: {}"), ch)
             }
         }
     }
 
     let start_quoting_from =
-        if input.len() == 0 {
+        if input.is_empty() {
             0
         } else if let Some(offset) = needs_quoting_from {
             offset
         } else {
             return if validator.end() {
-                Ok(Cow::Borrowed(input))
+                Ok((Cow::Borrowed(input), false))
             } else {
                 let mut out = String::with_capacity(input.len() + 2);
                 out.push('"');
                 out.push_str(input);
                 out.push('"');
-                Ok(Cow::Owned(out))
+                Ok((Cow::Owned(out), true))
             };
         };
 
@@ -149,9 +615,81 @@ pub fn quote_if_needed<'a, Spec, WQImpl>(
     let mut out = String::with_capacity(input.len() + 3);
     out.push('"');
     out.push_str(&input[0..start_quoting_from]);
-    quote_inner::<Spec>(&input[start_quoting_from..], &mut out)?;
+    quote_inner::<Spec, _>(&input[start_quoting_from..], &mut out)?;
     out.push('"');
-    Ok(Cow::Owned(out))
+    Ok((Cow::Owned(out), true))
+}
+
+/// encodes the input as a token if possible and as quoted string if not
+///
+/// This covers the `token | quoted-string` production used in many
+/// HTTP and MIME grammars. It works like `quote_if_needed` using a new
+/// (default) instance of `Token` as validator, i.e. `Token` decides what
+/// is a valid token and `Spec` how quoting is done.
+///
+/// # Error
+///
+/// `CoreError::InvalidChar` is returned if the input can neither be
+/// represented as token nor as quoted string.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec and token validator
+/// use quoted_string::test_utils::{TestSpec, TestUnquotedValidator};
+/// use quoted_string::encode_as_token_or_quoted;
+///
+/// let out = encode_as_token_or_quoted::<TestSpec, TestUnquotedValidator>("simple").unwrap();
+/// assert_eq!(out, "simple");
+/// let out = encode_as_token_or_quoted::<TestSpec, TestUnquotedValidator>("not simple").unwrap();
+/// assert_eq!(out, "\"not simple\"");
+/// ```
+pub fn encode_as_token_or_quoted<Spec, Token>(input: &str) -> Result<Cow<'_, str>, CoreError>
+    where Spec: GeneralQSSpec,
+          Token: WithoutQuotingValidator + Default
+{
+    quote_if_needed::<Spec, _>(input, &mut Token::default())
+}
+
+/// encodes a `name=value` parameter quoting the value if needed
+///
+/// The value is encoded like with `encode_as_token_or_quoted`, i.e. a new
+/// (default) instance of `Token` decides if it can be used as is and `Spec`
+/// how it is quoted otherwise. The name is used as is, it is the callers
+/// responsibility to make sure it is a valid parameter name. The `"; "`
+/// separating parameters is not included.
+///
+/// # Error
+///
+/// `CoreError::ZeroSizedValue` is returned if the name is empty and
+/// `CoreError::InvalidChar` if the value can neither be represented as
+/// token nor as quoted string.
+///
+/// # Example
+///
+/// ```
+/// // use your own Spec and token validator
+/// use quoted_string::test_utils::{TestSpec, TestUnquotedValidator};
+/// use quoted_string::encode_parameter_value;
+///
+/// let out = encode_parameter_value::<TestSpec, TestUnquotedValidator>("charset", "simple");
+/// assert_eq!(out.unwrap(), "charset=simple");
+/// let out = encode_parameter_value::<TestSpec, TestUnquotedValidator>("title", "not \"simple\"");
+/// assert_eq!(out.unwrap(), "title=\"not \\\"simple\\\"\"");
+/// ```
+pub fn encode_parameter_value<Spec, Token>(name: &str, value: &str) -> Result<String, CoreError>
+    where Spec: GeneralQSSpec,
+          Token: WithoutQuotingValidator + Default
+{
+    if name.is_empty() {
+        return Err(CoreError::ZeroSizedValue);
+    }
+    let value = encode_as_token_or_quoted::<Spec, Token>(value)?;
+    let mut out = String::with_capacity(name.len() + 1 + value.len());
+    out.push_str(name);
+    out.push('=');
+    out.push_str(&value);
+    Ok(out)
 }
 
 
@@ -160,7 +698,7 @@ mod test {
     // this import will become unused in future rust versions
     // but won't be removed for now for supporting current
     // rust versions
-    #[allow(warnings)]
+    #[allow(unused_imports, deprecated)]
     use std::ascii::AsciiExt;
     use test_utils::*;
     use error::CoreError;
@@ -171,7 +709,8 @@ mod test {
         let data = &[
             ("this is simple", "\"this is simple\""),
             ("with quotes\"  ", "\"with quotes\\\"  \""),
-            ("with slash\\  ", "\"with slash\\\\  \"")
+            ("with slash\\  ", "\"with slash\\\\  \""),
+            ("\"\\adjacent\\\"", "\"\\\"\\\\adjacent\\\\\\\"\"")
         ];
         for &(unquoted, quoted) in data.iter() {
             let got_quoted = quote::<TestSpec>(unquoted).unwrap();
@@ -198,7 +737,7 @@ mod test {
         let out = quote_if_needed::<TestSpec, _>("abcd.e", &mut without_quoting).unwrap();
         assert_eq!(out, Cow::Borrowed("abcd.e"));
         assert_eq!(without_quoting.count, 6);
-        assert_eq!(without_quoting.last_was_dot, false)
+        assert!(!without_quoting.last_was_dot)
     }
 
     #[test]
@@ -236,4 +775,211 @@ mod test {
         assert_eq!(out, expected);
         assert_eq!(without_quoting.count, 0);
     }
+
+    #[test]
+    fn write_header_short() {
+        let mut out = String::new();
+        write_quoted_string_header::<TestSpec, _>("X-Name", "a\\b", &mut out).unwrap();
+        assert_eq!(out, "X-Name: \"a\\\\b\"\r\n");
+    }
+
+    #[test]
+    fn write_header_folds_at_whitespace() {
+        let value = "0123456789 ".repeat(10);
+        let mut out = String::new();
+        write_quoted_string_header::<TestSpec, _>("X-Name", &value, &mut out).unwrap();
+        for line in out.split("\r\n") {
+            assert!(line.len() <= 78);
+            assert!(!line.trim().is_empty() || line.is_empty());
+        }
+        let unfolded = out.replace("\r\n", "");
+        assert_eq!(unfolded, format!("X-Name: \"{}\"", value));
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    struct EscapeSpaceSpec;
+
+    impl GeneralQSSpec for EscapeSpaceSpec {
+        type Quoting = Self;
+        type Parsing = TestParsingImpl;
+    }
+
+    impl QuotingClassifier for EscapeSpaceSpec {
+        fn classify_for_quoting(pcp: PartialCodePoint) -> QuotingClass {
+            if pcp.as_u8() == b' ' {
+                QuotingClass::NeedsQuoting
+            } else {
+                TestSpec::classify_for_quoting(pcp)
+            }
+        }
+    }
+
+    #[test]
+    fn write_header_does_not_fold_escaped_whitespace() {
+        let value = "0123456789 ".repeat(10);
+        let mut out = String::new();
+        write_quoted_string_header::<EscapeSpaceSpec, _>("X-Name", &value, &mut out).unwrap();
+        assert_eq!(out, format!("X-Name: \"{}\"\r\n", value.replace(' ', "\\ ")));
+    }
+
+    #[test]
+    fn write_header_can_not_fold_without_whitespace() {
+        let value = "a".repeat(100);
+        let mut out = String::new();
+        write_quoted_string_header::<TestSpec, _>("X-Name", &value, &mut out).unwrap();
+        assert_eq!(out, format!("X-Name: \"{}\"\r\n", value));
+    }
+
+    #[test]
+    fn write_header_invalid_char() {
+        let mut out = String::new();
+        let res = write_quoted_string_header::<TestSpec, _>("X-Name", "→", &mut out);
+        assert_eq!(res, Err(CoreError::InvalidChar));
+        assert_eq!(out, "");
+    }
+
+    #[test]
+    fn quote_if_needed_with_flag_unneeded() {
+        let mut without_quoting = TestUnquotedValidator::new();
+        let (out, was_quoted) =
+            quote_if_needed_with_flag::<TestSpec, _>("abcdef", &mut without_quoting).unwrap();
+        assert_eq!(out, Cow::Borrowed("abcdef"));
+        assert!(!was_quoted);
+    }
+
+    #[test]
+    fn quote_if_needed_with_flag_needed() {
+        let mut without_quoting = TestUnquotedValidator::new();
+        let (out, was_quoted) =
+            quote_if_needed_with_flag::<TestSpec, _>("abc", &mut without_quoting).unwrap();
+        assert_eq!(out, "\"abc\"");
+        assert!(was_quoted);
+
+        let mut without_quoting = TestUnquotedValidator::new();
+        let (out, was_quoted) =
+            quote_if_needed_with_flag::<TestSpec, _>("", &mut without_quoting).unwrap();
+        assert_eq!(out, "\"\"");
+        assert!(was_quoted);
+    }
+
+    #[test]
+    fn quote_append_appends() {
+        let mut out = String::from("x=");
+        quote_append::<TestSpec>("a\\b", &mut out).unwrap();
+        assert_eq!(out, "x=\"a\\\\b\"");
+    }
+
+    #[test]
+    fn quote_append_error_leaves_target_unchanged() {
+        let mut out = String::from("x=");
+        let res = quote_append::<TestSpec>("a\0b", &mut out);
+        assert_eq!(res, Err(CoreError::InvalidChar));
+        assert_eq!(out, "x=");
+    }
+
+    #[test]
+    fn encode_as_token_or_quoted_variants() {
+        let out = encode_as_token_or_quoted::<TestSpec, TestUnquotedValidator>("simple");
+        assert_eq!(out, Ok(Cow::Borrowed("simple")));
+        let out = encode_as_token_or_quoted::<TestSpec, TestUnquotedValidator>("a\"b");
+        assert_eq!(out, Ok(Cow::Owned("\"a\\\"b\"".to_owned())));
+        let out = encode_as_token_or_quoted::<TestSpec, TestUnquotedValidator>("a\0b");
+        assert_eq!(out, Err(CoreError::InvalidChar));
+    }
+
+    #[test]
+    fn quote_to_writer_invalid() {
+        let mut out = String::new();
+        let res = quote_to_writer::<TestSpec, _>("a\0", &mut out);
+        assert_eq!(res, Err(CoreError::InvalidChar));
+    }
+
+    #[test]
+    fn writable_quoted_string() {
+        let qs = WritableQuotedString::<TestSpec>::new("a\\b");
+        assert_eq!(qs.to_string(), "\"a\\\\b\"");
+        assert_eq!(format!("[{}]", qs), "[\"a\\\\b\"]");
+    }
+
+    #[test]
+    fn writable_quoted_string_invalid() {
+        use std::fmt::Write;
+        let mut out = String::new();
+        let res = write!(out, "{}", WritableQuotedString::<TestSpec>::new("a\0"));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn encode_parameter_value_token() {
+        let out = encode_parameter_value::<TestSpec, TestUnquotedValidator>("a", "abcdef");
+        assert_eq!(out, Ok("a=abcdef".to_owned()));
+    }
+
+    #[test]
+    fn encode_parameter_value_quoted() {
+        let out = encode_parameter_value::<TestSpec, TestUnquotedValidator>("a", "ab\\ef");
+        assert_eq!(out, Ok("a=\"ab\\\\ef\"".to_owned()));
+    }
+
+    #[test]
+    fn encode_parameter_value_errors() {
+        let out = encode_parameter_value::<TestSpec, TestUnquotedValidator>("", "abcdef");
+        assert_eq!(out, Err(CoreError::ZeroSizedValue));
+        let out = encode_parameter_value::<TestSpec, TestUnquotedValidator>("a", "→");
+        assert_eq!(out, Err(CoreError::InvalidChar));
+    }
+
+    #[test]
+    fn append_quoted_pair_and_qtext() {
+        let mut out = String::new();
+        append_qtext('a', &mut out);
+        append_quoted_pair('\\', &mut out);
+        append_quoted_pair('→', &mut out);
+        assert_eq!(out, "a\\\\\\→");
+    }
+
+    #[test]
+    fn write_unquoted_or_quoted_token() {
+        let mut out = String::new();
+        write_unquoted_or_quoted::<TestSpec, TestUnquotedValidator, _>("abc.de", &mut out).unwrap();
+        assert_eq!(out, "abc.de");
+    }
+
+    #[test]
+    fn write_unquoted_or_quoted_quoted() {
+        let mut out = String::new();
+        write_unquoted_or_quoted::<TestSpec, TestUnquotedValidator, _>("abc.", &mut out).unwrap();
+        write_unquoted_or_quoted::<TestSpec, TestUnquotedValidator, _>("", &mut out).unwrap();
+        assert_eq!(out, "\"abc.\"\"\"");
+    }
+
+    #[test]
+    fn write_unquoted_or_quoted_invalid() {
+        let mut out = String::new();
+        let res = write_unquoted_or_quoted::<TestSpec, TestUnquotedValidator, _>("→", &mut out);
+        assert_eq!(res, Err(CoreError::InvalidChar));
+    }
+
+    #[test]
+    fn from_content_unchecked_escapes() {
+        assert_eq!(from_content_unchecked::<TestSpec>("a\"b\\"), "\"a\\\"b\\\\\"");
+        assert_eq!(from_content_unchecked::<TestSpec>(""), "\"\"");
+    }
+
+    #[test]
+    fn from_content_unchecked_does_not_check() {
+        assert_eq!(from_content_unchecked::<TestSpec>("a→"), "\"a→\"");
+    }
+
+    #[test]
+    fn quote_bytes_simple() {
+        assert_eq!(quote_bytes::<TestSpec>(b"a\\b\"c"), Ok(b"\"a\\\\b\\\"c\"".to_vec()));
+        assert_eq!(quote_bytes::<TestSpec>(b""), Ok(b"\"\"".to_vec()));
+    }
+
+    #[test]
+    fn quote_bytes_invalid() {
+        assert_eq!(quote_bytes::<TestSpec>(b"a\0"), Err(CoreError::InvalidChar));
+        assert_eq!(quote_bytes::<TestSpec>(&[b'a', 0xff]), Err(CoreError::InvalidChar));
+    }
 }
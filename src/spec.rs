@@ -1,13 +1,49 @@
 //! This module contains types for specifying what kind of quoted string is used
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::str::Chars;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
 use error::CoreError;
 
 /// type to specify the quoting classifier and parsing implementation
 ///
-/// This is normally a zero-sized type.
-pub trait GeneralQSSpec: Clone+Debug {
+/// This is normally a zero-sized type. It is required to implement `Eq`
+/// and `Hash` so that it can be used as key when caching data derived from
+/// it (e.g. the table returned by `QuotingClassifier::all_ascii_qtext`).
+pub trait GeneralQSSpec: Clone+Debug+Eq+Hash {
     type Quoting: QuotingClassifier;
     type Parsing: ParsingImpl;
+
+    /// a short human readable description of the spec for use in diagnostic messages
+    ///
+    /// E.g. `"RFC 5322 quoted-string"`, by default this is just `"quoted-string"`.
+    const DESCRIPTION: &'static str = "quoted-string";
+
+    /// returns true if `ch` can appear after a `\` in a quoted-pair
+    ///
+    /// This delegates to `Self::Parsing::can_be_quoted`.
+    fn quoted_pair_char_is_valid(ch: char) -> bool {
+        Self::Parsing::can_be_quoted(PartialCodePoint::from_code_point(ch as u32))
+    }
+
+    /// hook to validate a quoted string as a whole
+    ///
+    /// It is only ever called with the quoted string itself, never with
+    /// any text following it. The functions taking a quoted string (e.g.
+    /// `validate`, `to_content` or `ContentChars::from_str`) call it with
+    /// their input before decoding it, while `parse` and `parse_with_config`
+    /// call it with the parsed quoted string once the closing `'"'` was
+    /// found. It can be used to enforce constraints like length limits.
+    ///
+    /// `StreamingParser` does not call it as it never has the quoted string
+    /// as a `&str`, neither does `ContentChars::from_parts_unchecked`.
+    ///
+    /// By default this does nothing.
+    fn pre_validate(_input: &str) -> Result<(), CoreError> {
+        Ok(())
+    }
 }
 
 /// Type to provide a quoting classification method.
@@ -22,6 +58,31 @@ pub trait QuotingClassifier {
     /// differ between different utf-8 characters, which happens
     /// to be fine for all supported quoting use cases.
     fn classify_for_quoting(pcp: PartialCodePoint) -> QuotingClass;
+
+    /// returns a table containing for each us-ascii char if it is `QuotingClass::QText`
+    ///
+    /// This calls `classify_for_quoting` for all 128 us-ascii code points.
+    /// It can be used by spec authors to create a lookup table once
+    /// (e.g. using `lazy_static`) instead of classifying each char in hot paths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quoted_string::test_utils::TestSpec;
+    /// use quoted_string::spec::QuotingClassifier;
+    ///
+    /// let table = TestSpec::all_ascii_qtext();
+    /// assert!(table[b'a' as usize]);
+    /// assert!(!table[b'"' as usize]);
+    /// ```
+    fn all_ascii_qtext() -> [bool; 128] {
+        let mut table = [false; 128];
+        for (code_point, is_qtext) in table.iter_mut().enumerate() {
+            let class = Self::classify_for_quoting(PartialCodePoint::from_code_point(code_point as u32));
+            *is_qtext = class == QuotingClass::QText;
+        }
+        table
+    }
 }
 
 /// Represents if a char can be contained in a quoted string and if it needs escapeing
@@ -35,6 +96,14 @@ pub enum QuotingClass {
     Invalid
 }
 
+impl QuotingClass {
+
+    /// returns true if the char can appear in a quoted string (with or without quoting)
+    pub fn is_representable(&self) -> bool {
+        *self != QuotingClass::Invalid
+    }
+}
+
 /// Used to validate if a string is valid without beeing quoted.
 ///
 /// Depending on the complexity of the underlying grammar this types
@@ -63,10 +132,26 @@ pub trait WithoutQuotingValidator {
     ///   is done by the algorithm using it
     /// - so for many cases this is just true (the default impl)
     fn end(&self) -> bool { true }
+
+    /// calls `next` for each char of `s` stopping at the first char it returns false for
+    ///
+    /// Returns `Err` with the byte index of the first char `next` returned
+    /// false for, or `Ok(())` if all chars were accepted. This is used by
+    /// `quote_if_needed`, validators which can check multiple chars at once
+    /// more efficiently can override it.
+    fn validate_sequence(&mut self, s: &str) -> Result<(), usize> {
+        for (idx, ch) in s.char_indices() {
+            if !self.next(PartialCodePoint::from_code_point(ch as u32)) {
+                return Err(idx);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// State used when parsing a quoted string
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum State<T: Copy+Eq+Debug> {
     /// The initial state
     Start,
@@ -83,19 +168,80 @@ pub enum State<T: Copy+Eq+Debug> {
     End
 }
 
+/// writes a human readable name of the state, e.g. `Normal`
+///
+/// `Custom` states are written using the `Display` impl of the custom state.
+impl<T> Display for State<T>
+    where T: Copy+Eq+Debug+Display
+{
+    fn fmt(&self, fter: &mut fmt::Formatter) -> fmt::Result {
+        use self::State::*;
+        match *self {
+            Start => fter.write_str("Start"),
+            Normal => fter.write_str("Normal"),
+            Failed => fter.write_str("Failed"),
+            QPStart => fter.write_str("QPStart"),
+            Custom(ref custom) => Display::fmt(custom, fter),
+            End => fter.write_str("End")
+        }
+    }
+}
+
 /// This normally zero sized type provides functions for parsing a quoted string
+///
+/// The `ScanAutomaton` handles the start and end `'"'` and the start of
+/// quoted-pairs itself, everything else is delegated to this type.
 pub trait ParsingImpl: Copy+Eq+Debug {
+    /// returns true if `bch` can appear in a quoted-pair (i.e. after a `'\\'`)
+    ///
+    /// This is called for the char following the `'\\'` of a quoted-pair,
+    /// if it returns false parsing fails with `CoreError::UnquoteableCharQuoted`.
     fn can_be_quoted(bch: PartialCodePoint) -> bool;
+
+    /// handles any char in the normal state except `'"'` and `'\\'`
+    ///
+    /// Returns the next state and if `bch` is part of the content (i.e. should
+    /// be emitted) or not (e.g. non-semantic white space).
     fn handle_normal_state(bch: PartialCodePoint) -> Result<(State<Self>, bool), CoreError>;
+
+    /// advances a custom state, returning the next state and if `pcp` should be emitted
+    ///
+    /// This only needs to be implemented if `handle_normal_state` can return
+    /// a `State::Custom` state.
     fn advance(&self, _pcp: PartialCodePoint) -> Result<(State<Self>, bool), CoreError> {
         unreachable!("[BUG] custom state is not used, so advance is unreachable")
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ScanAutomaton<T: ParsingImpl> {
     state: State<T>,
-    last_was_emit: bool
+    last_was_emit: bool,
+    // counters are not part of the state, so they are ignored by `PartialEq` and `Hash`
+    total_emitted: usize,
+    total_advanced: usize
+}
+
+impl<Impl> PartialEq for ScanAutomaton<Impl>
+    where Impl: ParsingImpl
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.state == other.state && self.last_was_emit == other.last_was_emit
+    }
+}
+
+impl<Impl> Eq for ScanAutomaton<Impl>
+    where Impl: ParsingImpl
+{}
+
+impl<Impl> Hash for ScanAutomaton<Impl>
+    where Impl: ParsingImpl + Hash
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.state.hash(state);
+        self.last_was_emit.hash(state);
+    }
 }
 
 impl<Impl> ScanAutomaton<Impl>
@@ -103,26 +249,99 @@ impl<Impl> ScanAutomaton<Impl>
 {
 
     pub fn new() -> Self {
-        ScanAutomaton { state: State::Start, last_was_emit: false }
+        ScanAutomaton {
+            state: State::Start,
+            last_was_emit: false,
+            total_emitted: 0,
+            total_advanced: 0
+        }
     }
 
     pub fn did_end(&self) -> bool {
         self.state == State::End
     }
 
+    /// returns the current state of the automaton
+    pub fn state(&self) -> State<Impl> {
+        self.state
+    }
+
+    /// returns how often `advance` returned `Ok(true)`
+    ///
+    /// If the automaton is fed with utf-8 bytes (like e.g. `parse` does) this
+    /// is the byte length of the content, if it is fed with code points it is
+    /// the number of chars of the content.
+    pub fn total_emitted_count(&self) -> usize {
+        self.total_emitted
+    }
+
+    /// returns the content of the quoted string the automaton was fed with
+    ///
+    /// The automaton is expected to have been fed with the utf-8 bytes of
+    /// `input` starting at it's begin (like e.g. `parse` does). Returns `None`
+    /// if the automaton did not (yet) reach the end of the quoted string or
+    /// `input` is too short.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quoted_string::test_utils::TestParsingImpl;
+    /// use quoted_string::spec::{ScanAutomaton, PartialCodePoint};
+    ///
+    /// let input = "\"a\\\"b\"; tail";
+    /// let mut automaton = ScanAutomaton::<TestParsingImpl>::new();
+    /// for bch in input.bytes() {
+    ///     automaton.advance(PartialCodePoint::from_utf8_byte(bch)).unwrap();
+    ///     if automaton.did_end() { break; }
+    /// }
+    /// assert_eq!(automaton.into_content_slice(input), Some("a\\\"b"));
+    /// ```
+    pub fn into_content_slice(self, input: &str) -> Option<&str> {
+        if !self.did_end() {
+            return None;
+        }
+        // the automaton advanced over the opening and closing '"'
+        input.get(1..self.total_advanced - 1)
+    }
+
     pub fn end(&mut self) -> Result<(), CoreError> {
         if self.did_end() {
             Ok(())
         } else {
-            Err(CoreError::DoesNotEndWithDQuotes.into())
+            Err(CoreError::DoesNotEndWithDQuotes)
         }
     }
 
+    /// returns the state `advance` would move the automaton to for `pcp`
+    ///
+    /// This does not modify the automaton, which allows looking ahead
+    /// e.g. when speculatively parsing. If `advance` would fail with an
+    /// error the same error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use quoted_string::test_utils::TestParsingImpl;
+    /// use quoted_string::spec::{ScanAutomaton, PartialCodePoint, State};
+    ///
+    /// let automaton = ScanAutomaton::<TestParsingImpl>::new();
+    /// let next = automaton.peek_state_for(PartialCodePoint::from_utf8_byte(b'"'));
+    /// assert_eq!(next, Ok(State::Normal));
+    /// assert_eq!(automaton.state(), State::Start);
+    /// ```
+    pub fn peek_state_for(&self, pcp: PartialCodePoint) -> Result<State<Impl>, CoreError> {
+        _advance_scan_automaton(self.state, pcp).map(|(state, _emit)| state)
+    }
+
     pub fn advance(&mut self, pcp: PartialCodePoint) -> Result<bool, CoreError> {
         match _advance_scan_automaton(self.state, pcp) {
             Ok((state, emit)) => {
                 self.state = state;
                 self.last_was_emit = emit;
+                self.total_advanced += 1;
+                if emit {
+                    self.total_emitted += 1;
+                }
                 Ok(emit)
             },
             Err(err) => {
@@ -133,6 +352,169 @@ impl<Impl> ScanAutomaton<Impl>
     }
 }
 
+impl<Impl> Default for ScanAutomaton<Impl>
+    where Impl: ParsingImpl
+{
+    fn default() -> Self {
+        ScanAutomaton::new()
+    }
+}
+
+/// A `ScanAutomaton` which additionally records the positions of emitted characters
+///
+/// It is expected to be fed with the utf-8 bytes of the input (i.e. using
+/// `PartialCodePoint::from_utf8_byte`) like e.g. `parse` and `to_content` do.
+/// For each emitted character the byte offset of it's first byte is recorded,
+/// utf-8 continuation bytes are not recorded. Therefore the n-th recorded position
+/// is the byte offset of the n-th char of the content in the original input.
+///
+/// # Example
+///
+/// ```
+/// use quoted_string::test_utils::TestParsingImpl;
+/// use quoted_string::spec::{ScanAutomatonWithPositions, PartialCodePoint};
+///
+/// let mut automaton = ScanAutomatonWithPositions::<TestParsingImpl>::new();
+/// for bch in r#""a\"b""#.bytes() {
+///     automaton.advance(PartialCodePoint::from_utf8_byte(bch)).unwrap();
+/// }
+/// automaton.end().unwrap();
+/// assert_eq!(automaton.emit_positions(), &[1, 3, 4]);
+/// ```
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct ScanAutomatonWithPositions<T: ParsingImpl> {
+    automaton: ScanAutomaton<T>,
+    offset: usize,
+    emit_positions: Vec<usize>
+}
+
+impl<Impl> ScanAutomatonWithPositions<Impl>
+    where Impl: ParsingImpl
+{
+
+    pub fn new() -> Self {
+        ScanAutomatonWithPositions {
+            automaton: ScanAutomaton::new(),
+            offset: 0,
+            emit_positions: Vec::new()
+        }
+    }
+
+    pub fn did_end(&self) -> bool {
+        self.automaton.did_end()
+    }
+
+    pub fn end(&mut self) -> Result<(), CoreError> {
+        self.automaton.end()
+    }
+
+    pub fn advance(&mut self, pcp: PartialCodePoint) -> Result<bool, CoreError> {
+        let emit = self.automaton.advance(pcp)?;
+        // only record the first byte of a (multi byte) utf-8 code point
+        if emit && pcp.as_u8() & 0xC0 != 0x80 {
+            self.emit_positions.push(self.offset);
+        }
+        self.offset += 1;
+        Ok(emit)
+    }
+
+    /// the byte offsets of all chars emitted so far
+    pub fn emit_positions(&self) -> &[usize] {
+        &self.emit_positions
+    }
+
+    /// returns the byte offsets of all emitted chars consuming self
+    pub fn into_emit_positions(self) -> Vec<usize> {
+        self.emit_positions
+    }
+}
+
+impl<Impl> Default for ScanAutomatonWithPositions<Impl>
+    where Impl: ParsingImpl
+{
+    fn default() -> Self {
+        ScanAutomatonWithPositions::new()
+    }
+}
+
+/// A `ScanAutomaton` which additionally counts the consumed bytes
+///
+/// It can either be fed with the utf-8 bytes of the input using `advance`
+/// (each call counts as one byte) or with the chars of the input using
+/// `advance_char` (each call counts as the utf-8 length of the char).
+/// This is useful to find out how far into a buffer a quoted string extends.
+///
+/// # Example
+///
+/// ```
+/// use quoted_string::test_utils::TestParsingImpl;
+/// use quoted_string::spec::CountingScanAutomaton;
+///
+/// let mut automaton = CountingScanAutomaton::<TestParsingImpl>::new();
+/// for ch in "\"a\\\"\"; tail".chars() {
+///     automaton.advance_char(ch).unwrap();
+///     if automaton.did_end() { break; }
+/// }
+/// assert_eq!(automaton.bytes_consumed(), 5);
+/// ```
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+pub struct CountingScanAutomaton<T: ParsingImpl> {
+    automaton: ScanAutomaton<T>,
+    bytes_consumed: usize
+}
+
+impl<Impl> CountingScanAutomaton<Impl>
+    where Impl: ParsingImpl
+{
+
+    pub fn new() -> Self {
+        CountingScanAutomaton {
+            automaton: ScanAutomaton::new(),
+            bytes_consumed: 0
+        }
+    }
+
+    pub fn did_end(&self) -> bool {
+        self.automaton.did_end()
+    }
+
+    pub fn end(&mut self) -> Result<(), CoreError> {
+        self.automaton.end()
+    }
+
+    /// advances the automaton with a utf-8 byte counting it as one byte
+    pub fn advance(&mut self, pcp: PartialCodePoint) -> Result<bool, CoreError> {
+        let emit = self.automaton.advance(pcp)?;
+        self.bytes_consumed += 1;
+        Ok(emit)
+    }
+
+    /// advances the automaton with a char counting it's utf-8 byte length
+    pub fn advance_char(&mut self, ch: char) -> Result<bool, CoreError> {
+        let emit = self.automaton.advance(PartialCodePoint::from_code_point(ch as u32))?;
+        self.bytes_consumed += ch.len_utf8();
+        Ok(emit)
+    }
+
+    /// the number of bytes successfully fed into the automaton
+    pub fn bytes_consumed(&self) -> usize {
+        self.bytes_consumed
+    }
+
+    /// returns the wrapped automaton consuming self
+    pub fn into_inner(self) -> ScanAutomaton<Impl> {
+        self.automaton
+    }
+}
+
+impl<Impl> Default for CountingScanAutomaton<Impl>
+    where Impl: ParsingImpl
+{
+    fn default() -> Self {
+        CountingScanAutomaton::new()
+    }
+}
+
 fn _advance_scan_automaton<Impl: ParsingImpl>(state: State<Impl>, pcp: PartialCodePoint)
     -> Result<(State<Impl>, bool), CoreError>
 {
@@ -157,16 +539,16 @@ fn _advance_scan_automaton<Impl: ParsingImpl>(state: State<Impl>, pcp: PartialCo
             if Impl::can_be_quoted(pcp) {
                 Ok((Normal, true))
             } else {
-                Err(CoreError::UnquoteableCharQuoted.into())
+                Err(CoreError::UnquoteableCharQuoted)
             }
         }
         Custom(inner) => {
             inner.advance(pcp)
         }
         End => {
-            Err(CoreError::QuotedStringAlreadyEnded.into())
+            Err(CoreError::QuotedStringAlreadyEnded)
         },
-        Failed => Err(CoreError::AdvancedFailedAutomaton.into())
+        Failed => Err(CoreError::AdvancedFailedAutomaton)
     }
 }
 
@@ -222,10 +604,49 @@ impl PartialCodePoint {
         }
     }
 }
+/// returns an iterator over the `PartialCodePoint`s of the chars of `s`
+///
+/// Each char is converted using `PartialCodePoint::from_code_point`.
+///
+/// # Example
+///
+/// ```
+/// use quoted_string::spec::{partial_code_points, PartialCodePoint};
+///
+/// let pcps = partial_code_points("a→").collect::<Vec<_>>();
+/// assert_eq!(pcps, &[
+///     PartialCodePoint::from_code_point('a' as u32),
+///     PartialCodePoint::from_code_point('→' as u32)
+/// ]);
+/// ```
+pub fn partial_code_points(s: &str) -> PartialCodePoints<'_> {
+    PartialCodePoints { inner: s.chars() }
+}
+
+/// Iterator over the `PartialCodePoint`s of the chars of a `str`
+///
+/// It is created through `partial_code_points`.
+#[derive(Debug, Clone)]
+pub struct PartialCodePoints<'a> {
+    inner: Chars<'a>
+}
+
+impl<'a> Iterator for PartialCodePoints<'a> {
+    type Item = PartialCodePoint;
 
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|ch| PartialCodePoint::from_code_point(ch as u32))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
 
 /// Allows unquoted text containing only `_ | a..z | A..Z | 0..9`
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default)]
 pub struct AsciiWordValidator;
 
 impl WithoutQuotingValidator for AsciiWordValidator {
@@ -233,4 +654,393 @@ impl WithoutQuotingValidator for AsciiWordValidator {
         let u8val = pcp.as_u8();
         u8val.is_ascii_alphanumeric() || u8val == b'_'
     }
-}
\ No newline at end of file
+}
+
+/// Allows unquoted RFC 5646 language tags like `en-US`
+///
+/// This accepts `a..z | A..Z | 0..9` and `'-'` as long as the `'-'` is neither
+/// the first nor the last char and does not directly follow another `'-'`.
+/// It does not check the structure of the subtags (e.g. their length).
+#[derive(Copy, Clone, Debug)]
+pub struct LanguageTagValidator {
+    last_was_hyphen: bool
+}
+
+impl Default for LanguageTagValidator {
+    fn default() -> Self {
+        LanguageTagValidator { last_was_hyphen: true }
+    }
+}
+
+impl WithoutQuotingValidator for LanguageTagValidator {
+    fn next(&mut self, pcp: PartialCodePoint) -> bool {
+        let u8val = pcp.as_u8();
+        if u8val.is_ascii_alphanumeric() {
+            self.last_was_hyphen = false;
+            true
+        } else if u8val == b'-' && !self.last_was_hyphen {
+            self.last_was_hyphen = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn end(&self) -> bool {
+        !self.last_was_hyphen
+    }
+}
+
+/// Allows unquoted RFC 5321/5322 `dot-atom`s like the local part `john.doe`
+///
+/// This accepts `atext` (`a..z | A..Z | 0..9` and ``!#$%&'*+-/=?^_`{|}~``)
+/// and `'.'` as long as the `'.'` is neither the first nor the last char and
+/// does not directly follow another `'.'`.
+#[derive(Copy, Clone, Debug)]
+pub struct DotAtomValidator {
+    last_was_dot: bool
+}
+
+impl Default for DotAtomValidator {
+    fn default() -> Self {
+        DotAtomValidator { last_was_dot: true }
+    }
+}
+
+impl WithoutQuotingValidator for DotAtomValidator {
+    fn next(&mut self, pcp: PartialCodePoint) -> bool {
+        let u8val = pcp.as_u8();
+        if u8val.is_ascii_alphanumeric() || b"!#$%&'*+-/=?^_`{|}~".contains(&u8val) {
+            self.last_was_dot = false;
+            true
+        } else if u8val == b'.' && !self.last_was_dot {
+            self.last_was_dot = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn end(&self) -> bool {
+        !self.last_was_dot
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    mod general_qs_spec {
+        use std::collections::HashMap;
+        use test_utils::*;
+        use super::super::{GeneralQSSpec, QuotingClassifier};
+
+        #[test]
+        fn description() {
+            assert_eq!(TestSpec::DESCRIPTION, "test-quoted-string");
+        }
+
+        // only uses the bounds of `GeneralQSSpec` so that they are known to suffice
+        fn cached_qtext<Spec: GeneralQSSpec>(cache: &mut HashMap<Spec, [bool; 128]>, spec: Spec)
+            -> [bool; 128]
+        {
+            *cache.entry(spec).or_insert_with(Spec::Quoting::all_ascii_qtext)
+        }
+
+        #[test]
+        fn can_be_used_as_cache_key() {
+            let mut cache = HashMap::new();
+            assert!(cached_qtext(&mut cache, TestSpec)[b'a' as usize]);
+            assert!(cache[&TestSpec][b'a' as usize]);
+        }
+
+        #[test]
+        fn quoted_pair_char_is_valid() {
+            assert!(TestSpec::quoted_pair_char_is_valid('a'));
+            assert!(TestSpec::quoted_pair_char_is_valid('"'));
+            assert!(!TestSpec::quoted_pair_char_is_valid('\0'));
+            assert!(!TestSpec::quoted_pair_char_is_valid('→'));
+        }
+    }
+
+    mod quoting_classifier {
+        use test_utils::*;
+        use super::super::{QuotingClassifier, QuotingClass};
+
+        #[test]
+        fn is_representable() {
+            assert!(QuotingClass::QText.is_representable());
+            assert!(QuotingClass::NeedsQuoting.is_representable());
+            assert!(!QuotingClass::Invalid.is_representable());
+        }
+
+        #[test]
+        fn all_ascii_qtext() {
+            let table = TestSpec::all_ascii_qtext();
+            for (idx, &is_qtext) in table.iter().enumerate() {
+                let expected = (0x20..0x7f).contains(&idx) && idx != b'"' as usize && idx != b'\\' as usize;
+                assert_eq!(is_qtext, expected, "code point: {}", idx);
+            }
+        }
+    }
+
+    mod without_quoting_validator {
+        use test_utils::*;
+        use super::super::{WithoutQuotingValidator, AsciiWordValidator};
+
+        #[test]
+        fn validate_sequence() {
+            assert_eq!(AsciiWordValidator.validate_sequence("ab_1"), Ok(()));
+            assert_eq!(AsciiWordValidator.validate_sequence("ab→c d"), Err(2));
+        }
+
+        #[test]
+        fn validate_sequence_keeps_state() {
+            let mut validator = TestUnquotedValidator::new();
+            assert_eq!(validator.validate_sequence("abc.d"), Ok(()));
+            assert!(!validator.end());
+            assert_eq!(validator.validate_sequence("e f"), Err(1));
+            assert!(validator.end());
+        }
+    }
+
+    mod language_tag_validator {
+        use test_utils::TestSpec;
+        use quote::quote_if_needed;
+        use super::super::LanguageTagValidator;
+
+        fn encode(input: &str) -> String {
+            quote_if_needed::<TestSpec, _>(input, &mut LanguageTagValidator::default())
+                .unwrap()
+                .into_owned()
+        }
+
+        #[test]
+        fn unquoted_language_tags() {
+            assert_eq!(encode("en"), "en");
+            assert_eq!(encode("en-US"), "en-US");
+            assert_eq!(encode("zh-Hant-TW"), "zh-Hant-TW");
+        }
+
+        #[test]
+        fn quotes_other_values() {
+            assert_eq!(encode("en US"), "\"en US\"");
+            assert_eq!(encode("-en"), "\"-en\"");
+            assert_eq!(encode("en--US"), "\"en--US\"");
+            assert_eq!(encode("en-"), "\"en-\"");
+        }
+    }
+
+    mod dot_atom_validator {
+        use test_utils::TestSpec;
+        use quote::quote_if_needed;
+        use super::super::DotAtomValidator;
+
+        fn encode(input: &str) -> String {
+            quote_if_needed::<TestSpec, _>(input, &mut DotAtomValidator::default())
+                .unwrap()
+                .into_owned()
+        }
+
+        #[test]
+        fn unquoted_dot_atoms() {
+            assert_eq!(encode("john"), "john");
+            assert_eq!(encode("john.doe"), "john.doe");
+            assert_eq!(encode("a.b+c!#$%&'*/=?^_`{|}~-"), "a.b+c!#$%&'*/=?^_`{|}~-");
+        }
+
+        #[test]
+        fn quotes_other_values() {
+            assert_eq!(encode("john doe"), "\"john doe\"");
+            assert_eq!(encode(".john"), "\".john\"");
+            assert_eq!(encode("john..doe"), "\"john..doe\"");
+            assert_eq!(encode("john."), "\"john.\"");
+            assert_eq!(encode("a@b"), "\"a@b\"");
+        }
+    }
+
+    mod scan_automaton {
+        use std::mem;
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{ScanAutomaton, PartialCodePoint, State};
+
+        #[test]
+        fn default_is_new() {
+            assert_eq!(ScanAutomaton::<TestParsingImpl>::default(), ScanAutomaton::new());
+        }
+
+        #[test]
+        fn total_emitted_count() {
+            let mut automaton = ScanAutomaton::<TestParsingImpl>::new();
+            for bch in "\"a\\\"b\n+-\nc\"".bytes() {
+                automaton.advance(PartialCodePoint::from_utf8_byte(bch)).unwrap();
+            }
+            assert!(automaton.did_end());
+            assert_eq!(automaton.total_emitted_count(), 4);
+        }
+
+        #[test]
+        fn eq_ignores_counters() {
+            let mut first = ScanAutomaton::<TestParsingImpl>::new();
+            let mut second = ScanAutomaton::<TestParsingImpl>::new();
+            for bch in "\"a".bytes() {
+                first.advance(PartialCodePoint::from_utf8_byte(bch)).unwrap();
+            }
+            for bch in "\"ab".bytes() {
+                second.advance(PartialCodePoint::from_utf8_byte(bch)).unwrap();
+            }
+            assert_ne!(first.total_emitted_count(), second.total_emitted_count());
+            assert_eq!(first, second);
+        }
+
+        #[test]
+        fn peek_state_for() {
+            let mut automaton = ScanAutomaton::<TestParsingImpl>::new();
+            let quote = PartialCodePoint::from_utf8_byte(b'"');
+            let slash = PartialCodePoint::from_utf8_byte(b'\\');
+            assert_eq!(automaton.peek_state_for(slash), Err(CoreError::DoesNotStartWithDQuotes));
+            automaton.advance(quote).unwrap();
+            assert_eq!(automaton.peek_state_for(slash), Ok(State::QPStart));
+            assert_eq!(automaton.peek_state_for(quote), Ok(State::End));
+            assert_eq!(automaton.state(), State::Normal);
+            assert_eq!(automaton.total_emitted_count(), 0);
+        }
+
+        #[test]
+        fn take_resets_automaton() {
+            let mut automaton = ScanAutomaton::<TestParsingImpl>::new();
+            automaton.advance(PartialCodePoint::from_utf8_byte(b'"')).unwrap();
+            automaton.advance(PartialCodePoint::from_utf8_byte(b'"')).unwrap();
+            let old = mem::take(&mut automaton);
+            assert!(old.did_end());
+            assert!(!automaton.did_end());
+            assert_eq!(automaton.advance(PartialCodePoint::from_utf8_byte(b'"')), Ok(false));
+        }
+
+        #[test]
+        fn into_content_slice() {
+            let input = "\"a\n+-\nb\"";
+            let mut automaton = ScanAutomaton::<TestParsingImpl>::new();
+            for bch in input.bytes() {
+                automaton.advance(PartialCodePoint::from_utf8_byte(bch)).unwrap();
+            }
+            assert_eq!(automaton.into_content_slice(input), Some("a\n+-\nb"));
+        }
+
+        #[test]
+        fn into_content_slice_not_ended() {
+            let mut automaton = ScanAutomaton::<TestParsingImpl>::new();
+            automaton.advance(PartialCodePoint::from_utf8_byte(b'"')).unwrap();
+            assert_eq!(automaton.into_content_slice("\"a\""), None);
+        }
+
+        #[test]
+        fn into_content_slice_input_too_short() {
+            let mut automaton = ScanAutomaton::<TestParsingImpl>::new();
+            for bch in "\"ab\"".bytes() {
+                automaton.advance(PartialCodePoint::from_utf8_byte(bch)).unwrap();
+            }
+            assert_eq!(automaton.into_content_slice("\"a"), None);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use serde::Serialize;
+        use serde::de::DeserializeOwned;
+        use test_utils::*;
+        use super::super::{ScanAutomaton, State};
+
+        fn assert_serde<T: Serialize + DeserializeOwned>() {}
+
+        #[test]
+        fn scan_automaton_is_serializable() {
+            assert_serde::<State<TestParsingImpl>>();
+            assert_serde::<ScanAutomaton<TestParsingImpl>>();
+        }
+    }
+
+    mod scan_automaton_with_positions {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{ScanAutomatonWithPositions, PartialCodePoint};
+
+        fn positions_of(input: &str) -> Result<Vec<usize>, CoreError> {
+            let mut automaton = ScanAutomatonWithPositions::<TestParsingImpl>::new();
+            for bch in input.bytes() {
+                automaton.advance(PartialCodePoint::from_utf8_byte(bch))?;
+            }
+            automaton.end()?;
+            Ok(automaton.into_emit_positions())
+        }
+
+        #[test]
+        fn simple() {
+            assert_eq!(positions_of("\"abc\"").unwrap(), vec![1, 2, 3]);
+        }
+
+        #[test]
+        fn skips_quoted_pair_start_and_non_semantic_parts() {
+            assert_eq!(positions_of("\"a\\\"b\n+-\nc\"").unwrap(), vec![1, 3, 4, 9]);
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(positions_of("\"\"").unwrap(), Vec::<usize>::new());
+        }
+
+        #[test]
+        fn missing_end() {
+            assert_eq!(positions_of("\"ab"), Err(CoreError::DoesNotEndWithDQuotes));
+        }
+    }
+
+    mod counting_scan_automaton {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::{CountingScanAutomaton, PartialCodePoint};
+
+        #[test]
+        fn counts_bytes() {
+            let mut automaton = CountingScanAutomaton::<TestParsingImpl>::new();
+            for bch in "\"a\\\"\"; tail".bytes() {
+                automaton.advance(PartialCodePoint::from_utf8_byte(bch)).unwrap();
+                if automaton.did_end() { break; }
+            }
+            assert_eq!(automaton.bytes_consumed(), 5);
+            assert!(automaton.into_inner().did_end());
+        }
+
+        #[test]
+        fn does_not_count_failed_advance() {
+            let mut automaton = CountingScanAutomaton::<TestParsingImpl>::new();
+            automaton.advance_char('"').unwrap();
+            assert_eq!(automaton.advance_char('\0'), Err(CoreError::InvalidChar));
+            assert_eq!(automaton.bytes_consumed(), 1);
+        }
+    }
+
+    mod state {
+        use std::fmt::{self, Display};
+        use super::super::State;
+
+        #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+        struct InComment;
+
+        impl Display for InComment {
+            fn fmt(&self, fter: &mut fmt::Formatter) -> fmt::Result {
+                fter.write_str("InComment")
+            }
+        }
+
+        #[test]
+        fn display() {
+            assert_eq!(State::<InComment>::Start.to_string(), "Start");
+            assert_eq!(State::<InComment>::Normal.to_string(), "Normal");
+            assert_eq!(State::<InComment>::Failed.to_string(), "Failed");
+            assert_eq!(State::<InComment>::QPStart.to_string(), "QPStart");
+            assert_eq!(State::Custom(InComment).to_string(), "InComment");
+            assert_eq!(State::<InComment>::End.to_string(), "End");
+        }
+    }
+}
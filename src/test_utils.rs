@@ -1,5 +1,7 @@
 //! provides an example implementation of quoted string spec's
 use std::default::Default;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
 
 use spec::{
     GeneralQSSpec,
@@ -11,12 +13,14 @@ use spec::{
 };
 use error::CoreError;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct TestSpec;
 
 impl GeneralQSSpec for TestSpec {
     type Quoting = Self;
     type Parsing = TestParsingImpl;
+
+    const DESCRIPTION: &'static str = "test-quoted-string";
 }
 
 impl QuotingClassifier for TestSpec {
@@ -34,7 +38,7 @@ impl QuotingClassifier for TestSpec {
 
 fn is_valid_pcp(pcp: PartialCodePoint) -> bool {
     let bch = pcp.as_u8();
-    b' ' <= bch && bch <= b'~'
+    (b' '..=b'~').contains(&bch)
 }
 
 /// a parsing implementations which allows non semantic stange thinks in it for testing purpose
@@ -49,6 +53,7 @@ fn is_valid_pcp(pcp: PartialCodePoint) -> bool {
 /// have non semantic soft line brakes (which are slight more complex to implement and less
 /// visible in error messages, so I used this think here)
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TestParsingImpl {
     StrangeInc(usize),
     StrangeDec(usize)
@@ -96,6 +101,42 @@ impl ParsingImpl for TestParsingImpl {
     }
 }
 
+/// a spec like `TestSpec` but accepting any char (e.g. non us-ascii ones) when parsing
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AnyCharSpec;
+
+impl GeneralQSSpec for AnyCharSpec {
+    type Quoting = TestSpec;
+    type Parsing = AnyCharParsing;
+}
+
+/// a parsing implementation accepting any char, both as is and in a quoted-pair
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub struct AnyCharParsing;
+
+impl ParsingImpl for AnyCharParsing {
+    fn can_be_quoted(_bch: PartialCodePoint) -> bool { true }
+    fn handle_normal_state(_bch: PartialCodePoint) -> Result<(State<Self>, bool), CoreError> {
+        Ok((State::Normal, true))
+    }
+}
+
+/// a spec like `TestSpec` but which rejects quoted strings longer than 10 bytes in `pre_validate`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MaxLenSpec;
+
+impl GeneralQSSpec for MaxLenSpec {
+    type Quoting = TestSpec;
+    type Parsing = TestParsingImpl;
+
+    fn pre_validate(input: &str) -> Result<(), CoreError> {
+        if input.len() > 10 {
+            Err(CoreError::LimitExceeded)
+        } else {
+            Ok(())
+        }
+    }
+}
 
 pub struct TestUnquotedValidator {
     pub count: usize,
@@ -120,7 +161,7 @@ impl WithoutQuotingValidator for TestUnquotedValidator {
         let bch = pcp.as_u8();
         let lwd = self.last_was_dot;
         let res = match bch {
-            b'a'...b'z' => {
+            b'a'..=b'z' => {
                 self.last_was_dot = false;
                 true
             }
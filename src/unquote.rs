@@ -1,6 +1,10 @@
-use spec::{GeneralQSSpec, ScanAutomaton, PartialCodePoint};
-use error::CoreError;
+use spec::{GeneralQSSpec, ScanAutomaton, PartialCodePoint, State, QuotingClassifier, QuotingClass};
+use error::{CoreError, AsciiOnlyError};
 use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::hash::Hasher;
+use std::mem;
+use iter::ContentChars;
 
 /// converts a quoted string into it's content
 ///
@@ -28,6 +32,7 @@ pub fn to_content<'a, Spec: GeneralQSSpec>(
     quoted_string: &'a str
 ) -> Result<Cow<'a, str>, CoreError>
 {
+    Spec::pre_validate(quoted_string)?;
     let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
     let mut continue_copy_from = None;
     for (idx, bch) in quoted_string.bytes().enumerate() {
@@ -72,6 +77,291 @@ pub fn to_content<'a, Spec: GeneralQSSpec>(
 
 }
 
+/// converts a quoted string into it's content requiring the content to be us-ascii
+///
+/// This is like `to_content` but additionally fails if the content
+/// contains any non us-ascii char, e.g. for contexts like RFC 5321 SMTP.
+///
+/// # Example
+/// ```
+/// //use your own Spec in practise
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::to_content_ascii_only;
+///
+/// let content = to_content_ascii_only::<TestSpec>("\"a\\\"b\"").unwrap();
+/// assert_eq!(content, "a\"b");
+/// ```
+pub fn to_content_ascii_only<Spec: GeneralQSSpec>(
+    quoted_string: &str
+) -> Result<Cow<'_, str>, AsciiOnlyError>
+{
+    let content = to_content::<Spec>(quoted_string)?;
+    if let Some((byte_offset, char_value)) = content.char_indices().find(|&(_, ch)| !ch.is_ascii()) {
+        return Err(AsciiOnlyError::NonAsciiAt { byte_offset, char_value });
+    }
+    Ok(content)
+}
+
+/// converts the quoted string in `quoted_string` into it's content in-place
+///
+/// This works like `to_content` but reuses the buffer of the passed in
+/// `String`. The input is validated before it is modified, so if an
+/// error is returned `quoted_string` is left unchanged.
+///
+/// # Example
+/// ```
+/// //use your own Spec in practise
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::unquote_in_place;
+///
+/// let mut value = String::from("\"ab\\\"c\"");
+/// unquote_in_place::<TestSpec>(&mut value).unwrap();
+/// assert_eq!(value, "ab\"c");
+/// ```
+pub fn unquote_in_place<Spec: GeneralQSSpec>(quoted_string: &mut String) -> Result<(), CoreError> {
+    Spec::pre_validate(quoted_string)?;
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    for bch in quoted_string.bytes() {
+        automaton.advance(PartialCodePoint::from_utf8_byte(bch))?;
+    }
+    automaton.end()?;
+
+    // the input is valid, so all bytes emitted a second time are
+    // the content, which is never longer than the part already read
+    let mut bytes = mem::take(quoted_string).into_bytes();
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    let mut write_idx = 0;
+    for read_idx in 0..bytes.len() {
+        let bch = bytes[read_idx];
+        let emit = automaton.advance(PartialCodePoint::from_utf8_byte(bch))
+            .expect("[BUG] input was validated but advancing failed");
+        if emit {
+            bytes[write_idx] = bch;
+            write_idx += 1;
+        }
+    }
+    bytes.truncate(write_idx);
+    *quoted_string = String::from_utf8(bytes)
+        .expect("[BUG] automaton caused a code point to be only partially emitted");
+    Ok(())
+}
+
+/// appends the content of a quoted string to `out`
+///
+/// This works like `to_content` but instead of allocating a new `String`
+/// (if the content is not a simple slice of the input) it writes directly
+/// into the passed in buffer. If the input is not a valid quoted string
+/// `out` is left unchanged.
+///
+/// Returns true if anything was appended, i.e. if the content is not empty.
+///
+/// # Example
+/// ```
+/// //use your own Spec in practise
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::append_content_to;
+///
+/// let mut out = String::from("content: ");
+/// let appended = append_content_to::<TestSpec>("\"ab\\\"c\n\nde\"", &mut out).unwrap();
+/// assert!(appended);
+/// assert_eq!(out, "content: ab\"cde");
+/// ```
+pub fn append_content_to<Spec: GeneralQSSpec>(
+    quoted_string: &str,
+    out: &mut String
+) -> Result<bool, CoreError>
+{
+    Spec::pre_validate(quoted_string)?;
+    let start_len = out.len();
+    let res = append_content_inner::<Spec>(quoted_string, out);
+    if res.is_err() {
+        out.truncate(start_len);
+    }
+    res.map(|()| out.len() > start_len)
+}
+
+fn append_content_inner<Spec: GeneralQSSpec>(
+    quoted_string: &str,
+    out: &mut String
+) -> Result<(), CoreError>
+{
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    let mut run_start = None;
+    for (idx, bch) in quoted_string.bytes().enumerate() {
+        let emit = automaton.advance(PartialCodePoint::from_utf8_byte(bch))?;
+        if emit {
+            if run_start.is_none() {
+                run_start = Some(idx);
+            }
+        } else if let Some(start) = run_start.take() {
+            let run = quoted_string.get(start..idx)
+                .expect("[BUG] automaton caused a code point to be only partially emitted");
+            out.push_str(run);
+        }
+    }
+    automaton.end()?;
+    // the closing '"' is never emitted so there can be no unfinished run
+    debug_assert!(run_start.is_none());
+    Ok(())
+}
+
+/// checks if the quoted string contains any quoted-pairs
+///
+/// This returns early with `Ok(true)` on the first quoted-pair, which
+/// means that the part of the input after it is _not_ validated. If
+/// no quoted-pair is found the whole input is validated.
+///
+/// # Example
+/// ```
+/// //use your own Spec in practise
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::has_quoted_pairs;
+///
+/// assert_eq!(has_quoted_pairs::<TestSpec>("\"simple\""), Ok(false));
+/// assert_eq!(has_quoted_pairs::<TestSpec>("\"a\\\"b\""), Ok(true));
+/// assert!(has_quoted_pairs::<TestSpec>("simple").is_err());
+/// ```
+pub fn has_quoted_pairs<Spec: GeneralQSSpec>(quoted_string: &str) -> Result<bool, CoreError> {
+    Spec::pre_validate(quoted_string)?;
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    for bch in quoted_string.bytes() {
+        automaton.advance(PartialCodePoint::from_utf8_byte(bch))?;
+        if automaton.state() == State::QPStart {
+            return Ok(true);
+        }
+    }
+    automaton.end()?;
+    Ok(false)
+}
+
+/// feeds the content of a quoted string into the given hasher
+///
+/// The hash is based on the decoded content, i.e. quoted strings which
+/// only differ in the way the content is encoded (e.g. `"ab"` and `"a\b"`)
+/// hash the same. This is meant to be used in `Hash` implementations of
+/// types wrapping quoted strings which compare them by content (e.g. using
+/// `ContentChars`).
+///
+/// If an error is returned parts of the content might already have
+/// been written to `state`.
+///
+/// # Example
+/// ```
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::Hasher;
+/// //use your own Spec in practise
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::quoted_string_hash;
+///
+/// let mut a = DefaultHasher::new();
+/// quoted_string_hash::<TestSpec, _>("\"ab\"", &mut a).unwrap();
+/// let mut b = DefaultHasher::new();
+/// quoted_string_hash::<TestSpec, _>("\"a\\b\"", &mut b).unwrap();
+/// assert_eq!(a.finish(), b.finish());
+/// ```
+pub fn quoted_string_hash<Spec, H>(quoted_string: &str, state: &mut H) -> Result<(), CoreError>
+    where Spec: GeneralQSSpec,
+          H: Hasher
+{
+    for res in ContentChars::<Spec>::from_str(quoted_string) {
+        state.write_u32(res? as u32);
+    }
+    // like for `str` terminate the sequence so that the hash is prefix-free
+    state.write_u8(0xff);
+    Ok(())
+}
+
+/// removes all unnecessary quoted-pairs while keeping the surrounding `'"'`
+///
+/// A quoted-pair is unnecessary if the quoted char is classified as
+/// `QuotingClass::QText` by `Spec::Quoting`, e.g. `\\a` is replaced by `a`
+/// but `\\"` is kept, so that the result is still a valid quoted string.
+/// Everything else (including non-semantic parts) is kept as it is. If no
+/// quoted-pair has to be removed the input is returned borrowed.
+///
+/// # Error
+///
+/// Like `to_content` this fails if the input is not exactly one valid
+/// quoted string.
+///
+/// # Example
+///
+/// ```
+/// # use std::borrow::Cow;
+/// // use your own Spec
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::unquote_pairs_only;
+///
+/// let normalized = unquote_pairs_only::<TestSpec>("\"\\a\\\"b\"").unwrap();
+/// assert_eq!(normalized, "\"a\\\"b\"");
+/// let normalized = unquote_pairs_only::<TestSpec>("\"simple\"").unwrap();
+/// assert_eq!(normalized, Cow::Borrowed("\"simple\""));
+/// ```
+pub fn unquote_pairs_only<Spec: GeneralQSSpec>(quoted_string: &str) -> Result<Cow<'_, str>, CoreError> {
+    Spec::pre_validate(quoted_string)?;
+    let mut automaton = ScanAutomaton::<Spec::Parsing>::new();
+    let mut out = None;
+    let mut continue_copy_from = 0;
+    for (idx, bch) in quoted_string.bytes().enumerate() {
+        let is_quoted = automaton.state() == State::QPStart;
+        let pcp = PartialCodePoint::from_utf8_byte(bch);
+        automaton.advance(pcp)?;
+        if is_quoted && Spec::Quoting::classify_for_quoting(pcp) == QuotingClass::QText {
+            let buffer = out.get_or_insert_with(|| String::with_capacity(quoted_string.len()));
+            //SLICE_SAFE: idx-1 is the '\\' starting the quoted-pair and idx the start of a char
+            buffer.push_str(&quoted_string[continue_copy_from..idx-1]);
+            continue_copy_from = idx;
+        }
+    }
+    automaton.end()?;
+    Ok(match out {
+        Some(mut buffer) => {
+            buffer.push_str(&quoted_string[continue_copy_from..]);
+            Cow::Owned(buffer)
+        },
+        None => Cow::Borrowed(quoted_string)
+    })
+}
+
+/// compares the content of two quoted strings lexicographically (by char)
+///
+/// Like `quoted_string_hash` this is based on the decoded content, so it
+/// is consistent with comparing quoted strings using `ContentChars` and
+/// can be used in `Ord` implementations of types wrapping quoted strings.
+///
+/// # Error
+///
+/// Both quoted strings are always decoded completely, if either of them
+/// is invalid the first error encountered is returned.
+///
+/// # Example
+/// ```
+/// use std::cmp::Ordering;
+/// //use your own Spec in practise
+/// use quoted_string::test_utils::TestSpec;
+/// use quoted_string::cmp_content;
+///
+/// assert_eq!(cmp_content::<TestSpec>("\"ab\"", "\"a\\b\""), Ok(Ordering::Equal));
+/// assert_eq!(cmp_content::<TestSpec>("\"ab\"", "\"b\""), Ok(Ordering::Less));
+/// assert!(cmp_content::<TestSpec>("\"ab\"", "\"b").is_err());
+/// ```
+pub fn cmp_content<Spec: GeneralQSSpec>(left: &str, right: &str) -> Result<Ordering, CoreError> {
+    let mut left = ContentChars::<Spec>::from_str(left);
+    let mut right = ContentChars::<Spec>::from_str(right);
+    let mut ordering = Ordering::Equal;
+    loop {
+        let left_ch = left.next().transpose()?;
+        let right_ch = right.next().transpose()?;
+        if left_ch.is_none() && right_ch.is_none() {
+            return Ok(ordering);
+        }
+        // keep going after the order is known to catch errors in the rest of the input
+        if ordering == Ordering::Equal {
+            ordering = left_ch.cmp(&right_ch);
+        }
+    }
+}
+
 /// strips quotes if they exists
 ///
 /// returns None if the input does not start with `"` and ends with `"`
@@ -97,6 +387,24 @@ pub fn strip_dquotes(quoted_string: &str) -> Option<&str> {
     }
 }
 
+/// strips quotes if they exists and then trims leading and trailing white space
+///
+/// returns None if the input does not start with `"` and ends with `"`
+///
+/// Note that this neither unquotes quoted-pairs nor does it check if
+/// the trimmed white space was escaped, i.e. it's meant to be used
+/// on quoted strings without quoted-pairs.
+///
+/// # Example
+/// ```
+/// use quoted_string::strip_dquotes_and_trim;
+/// assert_eq!(strip_dquotes_and_trim("\" a b \""), Some("a b"));
+/// assert_eq!(strip_dquotes_and_trim(" a b "), None);
+/// ```
+pub fn strip_dquotes_and_trim(quoted_string: &str) -> Option<&str> {
+    strip_dquotes(quoted_string).map(str::trim)
+}
+
 
 #[cfg(test)]
 mod test {
@@ -175,9 +483,216 @@ mod test {
         }
     }
 
+    mod to_content_ascii_only {
+        use std::borrow::Cow;
+        use test_utils::*;
+        use error::{CoreError, AsciiOnlyError};
+        use super::super::to_content_ascii_only;
+
+        #[test]
+        fn ascii_content() {
+            let res = to_content_ascii_only::<AnyCharSpec>("\"abc\"");
+            assert_eq!(res, Ok(Cow::Borrowed("abc")));
+        }
+
+        #[test]
+        fn non_ascii_content() {
+            let res = to_content_ascii_only::<AnyCharSpec>("\"a\\äb\"");
+            assert_eq!(res, Err(AsciiOnlyError::NonAsciiAt { byte_offset: 1, char_value: 'ä' }));
+        }
+
+        #[test]
+        fn invalid_quoted_string() {
+            let res = to_content_ascii_only::<TestSpec>("\"abc");
+            assert_eq!(res, Err(AsciiOnlyError::Core(CoreError::DoesNotEndWithDQuotes)));
+        }
+    }
+
+    mod unquote_in_place {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::unquote_in_place;
+
+        #[test]
+        fn unquotes() {
+            let mut value = String::from("\"a\\\\b\n+-\nc\\\"\"");
+            unquote_in_place::<TestSpec>(&mut value).unwrap();
+            assert_eq!(value, "a\\bc\"");
+        }
+
+        #[test]
+        fn empty_content() {
+            let mut value = String::from("\"\"");
+            unquote_in_place::<TestSpec>(&mut value).unwrap();
+            assert_eq!(value, "");
+        }
+
+        #[test]
+        fn error_leaves_input_unchanged() {
+            let mut value = String::from("\"a\\\"b");
+            let res = unquote_in_place::<TestSpec>(&mut value);
+            assert_eq!(res, Err(CoreError::DoesNotEndWithDQuotes));
+            assert_eq!(value, "\"a\\\"b");
+        }
+    }
+
+    mod append_content_to {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::append_content_to;
+
+        #[test]
+        fn simple() {
+            let mut out = String::from("x");
+            assert_eq!(append_content_to::<TestSpec>(r#""abc""#, &mut out), Ok(true));
+            assert_eq!(out, "xabc");
+        }
+
+        #[test]
+        fn with_quoted_pairs_and_non_semantic_ws() {
+            let mut out = String::new();
+            let res = append_content_to::<TestSpec>("\"a\\\"b\n+-\nc\\\\\"", &mut out);
+            assert_eq!(res, Ok(true));
+            assert_eq!(out, "a\"bc\\");
+        }
+
+        #[test]
+        fn empty() {
+            let mut out = String::from("x");
+            assert_eq!(append_content_to::<TestSpec>(r#""""#, &mut out), Ok(false));
+            assert_eq!(out, "x");
+        }
+
+        #[test]
+        fn error_leaves_out_unchanged() {
+            let mut out = String::from("x");
+            let res = append_content_to::<TestSpec>(r#""ab\"cd"#, &mut out);
+            assert_eq!(res, Err(CoreError::DoesNotEndWithDQuotes));
+            assert_eq!(out, "x");
+        }
+    }
+
+    mod has_quoted_pairs {
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::has_quoted_pairs;
+
+        #[test]
+        fn without_quoted_pairs() {
+            assert_eq!(has_quoted_pairs::<TestSpec>("\"hy \n\nthere\""), Ok(false));
+        }
 
+        #[test]
+        fn with_quoted_pair() {
+            assert_eq!(has_quoted_pairs::<TestSpec>(r#""a\bc""#), Ok(true));
+        }
 
+        #[test]
+        fn returns_early() {
+            assert_eq!(has_quoted_pairs::<TestSpec>(r#""a\bc"#), Ok(true));
+        }
 
+        #[test]
+        fn invalid() {
+            assert_eq!(has_quoted_pairs::<TestSpec>("\"abc"), Err(CoreError::DoesNotEndWithDQuotes));
+        }
+    }
+
+    mod quoted_string_hash {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::Hasher;
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::quoted_string_hash;
+
+        fn hash(input: &str) -> Result<u64, CoreError> {
+            let mut hasher = DefaultHasher::new();
+            quoted_string_hash::<TestSpec, _>(input, &mut hasher)?;
+            Ok(hasher.finish())
+        }
+
+        #[test]
+        fn same_content_same_hash() {
+            assert_eq!(hash("\"ab\""), hash("\"\\a\\b\""));
+            assert_eq!(hash("\"ab\""), hash("\"a\n\nb\""));
+        }
+
+        #[test]
+        fn different_content_different_hash() {
+            assert_ne!(hash("\"ab\"").unwrap(), hash("\"abc\"").unwrap());
+        }
+
+        #[test]
+        fn invalid() {
+            assert_eq!(hash("\"ab"), Err(CoreError::DoesNotEndWithDQuotes));
+        }
+    }
+
+    mod unquote_pairs_only {
+        use std::borrow::Cow;
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::unquote_pairs_only;
+
+        #[test]
+        fn without_quoted_pairs_is_borrowed() {
+            let res = unquote_pairs_only::<TestSpec>("\"a b\n+-\n\"");
+            assert_eq!(res, Ok(Cow::Borrowed("\"a b\n+-\n\"")));
+        }
+
+        #[test]
+        fn removes_unnecessary_quoted_pairs() {
+            let res = unquote_pairs_only::<TestSpec>("\"\\a b\\c\\\\\"");
+            assert_eq!(res, Ok(Cow::Owned("\"a bc\\\\\"".to_owned())));
+        }
+
+        #[test]
+        fn keeps_necessary_quoted_pairs() {
+            let res = unquote_pairs_only::<TestSpec>("\"\\\"\\\\\"");
+            assert_eq!(res, Ok(Cow::Borrowed("\"\\\"\\\\\"")));
+        }
+
+        #[test]
+        fn invalid() {
+            let res = unquote_pairs_only::<TestSpec>("\"\\a");
+            assert_eq!(res, Err(CoreError::DoesNotEndWithDQuotes));
+            let res = unquote_pairs_only::<TestSpec>("\"\\a\" b");
+            assert_eq!(res, Err(CoreError::QuotedStringAlreadyEnded));
+        }
+    }
+
+    mod cmp_content {
+        use std::cmp::Ordering;
+        use test_utils::*;
+        use error::CoreError;
+        use super::super::cmp_content;
+
+        #[test]
+        fn equal_content() {
+            assert_eq!(cmp_content::<TestSpec>("\"a\n\nb\"", "\"\\ab\""), Ok(Ordering::Equal));
+            assert_eq!(cmp_content::<TestSpec>("\"\"", "\"\""), Ok(Ordering::Equal));
+        }
+
+        #[test]
+        fn different_content() {
+            assert_eq!(cmp_content::<TestSpec>("\"ab\"", "\"ac\""), Ok(Ordering::Less));
+            assert_eq!(cmp_content::<TestSpec>("\"b\"", "\"ab\""), Ok(Ordering::Greater));
+        }
+
+        #[test]
+        fn prefix_is_less() {
+            assert_eq!(cmp_content::<TestSpec>("\"a\"", "\"ab\""), Ok(Ordering::Less));
+            assert_eq!(cmp_content::<TestSpec>("\"ab\"", "\"\""), Ok(Ordering::Greater));
+        }
+
+        #[test]
+        fn error_after_difference() {
+            let res = cmp_content::<TestSpec>("\"a\"", "\"b");
+            assert_eq!(res, Err(CoreError::DoesNotEndWithDQuotes));
+            let res = cmp_content::<TestSpec>("\"a\" x", "\"b\"");
+            assert_eq!(res, Err(CoreError::QuotedStringAlreadyEnded));
+        }
+    }
 
     mod strip_quotes {
         use super::super::strip_dquotes;
@@ -204,4 +719,22 @@ mod test {
         }
     }
 
+    mod strip_dquotes_and_trim {
+        use super::super::strip_dquotes_and_trim;
+
+        #[test]
+        fn trims_white_space() {
+            assert_eq!(strip_dquotes_and_trim("\" \tsimple \""), Some("simple"));
+        }
+
+        #[test]
+        fn only_white_space() {
+            assert_eq!(strip_dquotes_and_trim("\"  \""), Some(""));
+        }
+
+        #[test]
+        fn missing_quotes() {
+            assert_eq!(strip_dquotes_and_trim(" simple"), None);
+        }
+    }
 }